@@ -16,14 +16,13 @@ mod common;
 use crate::common::{SECONDARY_STACK, UART, init_heap};
 use aarch64_rt::{entry, start_core};
 use alloc::boxed::Box;
-use arm_sysregs::el1::accessors::read_mpidr_el1;
 use core::{
     cell::RefCell,
     fmt::Write,
     hint::spin_loop,
     sync::atomic::{AtomicBool, Ordering},
 };
-use percore::{Cores, ExceptionLock, PerCore, exception_free};
+use percore::{ExceptionLock, MpidrCores, PerCore, Uniform, exception_free};
 use smccc::{
     Hvc,
     psci::{cpu_off, system_off},
@@ -36,23 +35,10 @@ const CORE_COUNT: usize = 2;
 /// Used to signal that the secondary core has finished running and is about to turn itself off.
 static SECONDARY_FINISHED: AtomicBool = AtomicBool::new(false);
 
-/// Implementation of the `percore::Cores` trait.
-struct CoresImpl;
-
-// SAFETY: The `core_index` implementation checks that the affinity values are within the expected
-// range for a 2-core system, and then returns a unique value for each of the 2 cores.
-unsafe impl Cores for CoresImpl {
-    fn core_index() -> usize {
-        let mpidr_el1 = read_mpidr_el1();
-        assert_eq!(mpidr_el1.aff3(), 0);
-        assert_eq!(mpidr_el1.aff2(), 0);
-        assert_eq!(mpidr_el1.aff1(), 0);
-        let aff0 = mpidr_el1.aff0();
-        let index = aff0.into();
-        assert!(index < CORE_COUNT);
-        index
-    }
-}
+/// Implementation of the `percore::Cores` trait, for a single cluster of `CORE_COUNT` cores.
+///
+/// QEMU's `max` CPU doesn't set the MPIDR MT bit, so the core number is in Aff0.
+type CoresImpl = MpidrCores<Uniform<1, CORE_COUNT, 1, false>>;
 
 /// Mutable state for each core.
 static STATE: Once<PerCore<Box<[ExceptionLock<RefCell<u32>>]>, CoresImpl>> = Once::new();
@@ -13,7 +13,7 @@ mod common;
 use crate::common::{SECONDARY_STACK, UART};
 use aarch64_rt::{entry, start_core};
 use arm_sysregs::el1::{
-    accessors::{read_mpidr_el1, read_tpidr_el1, write_tpidr_el1},
+    accessors::{read_tpidr_el1, write_tpidr_el1},
     registers::TpidrEl1,
 };
 use core::{
@@ -23,7 +23,7 @@ use core::{
     sync::atomic::{AtomicBool, Ordering},
 };
 use percore::{
-    Cores, ExceptionLock,
+    Cores, ExceptionLock, MpidrCores, Uniform,
     derive::{
         PercoreLocalOffset,
         aarch64::{percore_calculate_local_offset, percore_copy_secondary_data},
@@ -43,23 +43,10 @@ const CORE_COUNT: usize = 2;
 /// Used to signal that the secondary core has finished running and is about to turn itself off.
 static SECONDARY_FINISHED: AtomicBool = AtomicBool::new(false);
 
-/// Implementation of the `percore::Cores` trait.
-struct CoresImpl;
-
-// SAFETY: The `core_index` implementation checks that the affinity values are within the expected
-// range for a 2-core system, and then returns a unique value for each of the 2 cores.
-unsafe impl Cores for CoresImpl {
-    fn core_index() -> usize {
-        let mpidr_el1 = read_mpidr_el1();
-        assert_eq!(mpidr_el1.aff3(), 0);
-        assert_eq!(mpidr_el1.aff2(), 0);
-        assert_eq!(mpidr_el1.aff1(), 0);
-        let aff0 = mpidr_el1.aff0();
-        let index = aff0.into();
-        assert!(index < CORE_COUNT);
-        index
-    }
-}
+/// Implementation of the `percore::Cores` trait, for a single cluster of `CORE_COUNT` cores.
+///
+/// QEMU's `max` CPU doesn't set the MPIDR MT bit, so the core number is in Aff0.
+type CoresImpl = MpidrCores<Uniform<1, CORE_COUNT, 1, false>>;
 
 percore_local_offset!(PercoreLocalOffsetImpl);
 struct PercoreLocalOffsetImpl;
@@ -4,7 +4,7 @@
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{ItemStatic, parse_macro_input};
+use syn::{ItemFn, ItemStatic, parse_macro_input};
 
 /// Marks the variable as percore, creating an instance for each core.
 ///
@@ -42,3 +42,63 @@ pub fn percore(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Registers the function to be called by `percore::derive::core_online` when a core comes online,
+/// e.g. to initialise its per-core state.
+///
+/// The function must have the signature `fn(core_index: usize)`. A pointer to it is placed in the
+/// `percore_online` linker section.
+///
+/// # Example
+///
+/// ```
+/// use percore::derive::percore_init;
+///
+/// #[percore_init]
+/// fn init_redistributor(core_index: usize) {
+///     // Wake up the GIC redistributor for the core...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn percore_init(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    lifecycle_hook(item, "percore_online", "__DATA,__percore_on")
+}
+
+/// Registers the function to be called by `percore::derive::core_offline` when a core goes offline,
+/// e.g. to tear down its per-core state before it is powered off.
+///
+/// The function must have the signature `fn(core_index: usize)`. A pointer to it is placed in the
+/// `percore_offline` linker section.
+#[proc_macro_attribute]
+pub fn percore_teardown(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    lifecycle_hook(item, "percore_offline", "__DATA,__percore_off")
+}
+
+/// Registers the function to be called by `percore::derive::core_resume` when a core resumes from
+/// suspend, after its percore area has been restored, e.g. to re-derive hardware handles which
+/// can't simply be copied.
+///
+/// The function must have the signature `fn(core_index: usize)`. A pointer to it is placed in the
+/// `percore_resume` linker section.
+#[proc_macro_attribute]
+pub fn percore_resume(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    lifecycle_hook(item, "percore_resume", "__DATA,__percore_resum")
+}
+
+/// Emits the given function along with a `LifecycleHook` pointing to it in the given linker section.
+fn lifecycle_hook(item: TokenStream, section: &str, macho_section: &str) -> TokenStream {
+    let item_fn = parse_macro_input!(item as ItemFn);
+    let name = &item_fn.sig.ident;
+
+    quote! {
+        #item_fn
+
+        const _: () = {
+            #[used]
+            #[cfg_attr(any(target_os = "none", target_os = "linux", target_os = "android", target_os = "fuchsia", target_os = "psp", target_os = "freebsd", target_os = "openbsd"), unsafe(link_section = #section))]
+            #[cfg_attr(any(target_os = "macos", target_os = "ios", target_os = "tvos"), unsafe(link_section = #macho_section))]
+            static HOOK: percore::derive::LifecycleHook = percore::derive::LifecycleHook::new(#name);
+        };
+    }
+    .into()
+}
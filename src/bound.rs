@@ -0,0 +1,166 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CoreSync, Cores, ExceptionFree};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+};
+
+/// Holds a value which is only valid on the CPU core which created it, such as an `Rc` or a raw
+/// MMIO handle, allowing it to be stored in a static or in a [`PerCore`](crate::PerCore) even if it
+/// isn't `Send` or `Sync`.
+///
+/// The value can only be accessed on the core which created it, with exceptions masked so that it
+/// can't be accessed from different exception contexts at the same time. To ensure that nothing
+/// which isn't `Send` can escape to a different exception context, the value is created by a
+/// closure which must be `Send`, and can only be accessed within a closure which must be `Send`
+/// and return a `Send` value.
+///
+/// If a `CoreBound` is dropped on a different core then its value is leaked rather than dropped.
+pub struct CoreBound<T, C: Cores> {
+    core_index: usize,
+    value: ManuallyDrop<T>,
+    _cores: PhantomData<fn() -> C>,
+}
+
+impl<T, C: Cores> CoreBound<T, C> {
+    /// Creates a new `CoreBound` bound to the current core, with the value returned by the given
+    /// function.
+    ///
+    /// Panics if the current core's index can't be determined.
+    pub fn new(f: impl FnOnce() -> T + Send) -> Self {
+        Self {
+            core_index: C::core_index(),
+            value: ManuallyDrop::new(f()),
+            _cores: PhantomData,
+        }
+    }
+
+    /// Returns the index of the core to which the value is bound.
+    pub fn core_index(&self) -> usize {
+        self.core_index
+    }
+
+    /// Returns whether the current core is the one to which the value is bound.
+    fn is_current_core(&self) -> bool {
+        C::try_core_index() == Some(self.core_index)
+    }
+
+    /// Calls the given function with a reference to the value, given a token proving that
+    /// exceptions are currently masked.
+    ///
+    /// Returns `None` without calling the function if the current core is not the one which created
+    /// the value.
+    pub fn with<'cs, R: Send>(
+        &'cs self,
+        _: ExceptionFree<'cs>,
+        f: impl FnOnce(&T) -> R + Send,
+    ) -> Option<R> {
+        self.is_current_core().then(|| f(&self.value))
+    }
+
+    /// Calls the given function with a reference to the value, given a token proving that
+    /// exceptions are currently masked, without checking the current core in release builds.
+    ///
+    /// # Safety
+    ///
+    /// The current core must be the one which created the value, i.e. `C::core_index()` must equal
+    /// [`core_index`](Self::core_index). This is only checked if debug assertions are enabled.
+    pub unsafe fn with_unchecked<'cs, R: Send>(
+        &'cs self,
+        _: ExceptionFree<'cs>,
+        f: impl FnOnce(&T) -> R + Send,
+    ) -> R {
+        debug_assert!(self.is_current_core());
+        f(&self.value)
+    }
+
+    /// Consumes the `CoreBound`, returning the wrapped value if the current core is the one which
+    /// created it, or the `CoreBound` back if not.
+    pub fn into_inner(self) -> Result<T, Self> {
+        if self.is_current_core() {
+            let mut this = ManuallyDrop::new(self);
+            // SAFETY: `this` is never used again, and won't be dropped.
+            Ok(unsafe { ManuallyDrop::take(&mut this.value) })
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T, C: Cores> Debug for CoreBound<T, C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("CoreBound")
+            .field("core_index", &self.core_index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, C: Cores> Drop for CoreBound<T, C> {
+    fn drop(&mut self) {
+        if self.is_current_core() {
+            // SAFETY: The value is never used again, and we are on the core which created it.
+            unsafe {
+                ManuallyDrop::drop(&mut self.value);
+            }
+        }
+    }
+}
+
+// SAFETY: The value can only be accessed or dropped on the core which created it, so moving the
+// `CoreBound` to a different core can't cause the value to be used there.
+unsafe impl<T, C: Cores> Send for CoreBound<T, C> {}
+
+// SAFETY: A shared reference only allows the value to be accessed on the core which created it,
+// with exceptions masked, and only within a `Send` closure which can't leak anything which isn't
+// `Send` to a different exception context.
+unsafe impl<T, C: Cores> Sync for CoreBound<T, C> {}
+
+// SAFETY: As above, the value can only be accessed with exceptions masked, so different exception
+// contexts can't access it concurrently.
+unsafe impl<T, C: Cores> CoreSync for CoreBound<T, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use std::{cell::RefCell, rc::Rc, thread};
+
+    #[test]
+    fn core_bound() {
+        FakeCoresImpl::set_core_index(0);
+        let bound = CoreBound::<_, FakeCoresImpl>::new(|| Rc::new(RefCell::new(42)));
+        assert_eq!(bound.core_index(), 0);
+
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        assert_eq!(
+            bound.with(token, |value| {
+                *value.borrow_mut() += 1;
+                Rc::strong_count(value)
+            }),
+            Some(1)
+        );
+
+        thread::scope(|s| {
+            s.spawn(|| {
+                FakeCoresImpl::set_core_index(1);
+                // SAFETY: There are no exceptions in the simulated environment of the tests.
+                let token = unsafe { ExceptionFree::new() };
+                assert_eq!(bound.with(token, |value| *value.borrow()), None);
+            });
+        });
+
+        // SAFETY: The value was created on core 0.
+        let value = unsafe { bound.with_unchecked(token, |value| *value.borrow()) };
+        assert_eq!(value, 43);
+
+        FakeCoresImpl::set_core_index(1);
+        let bound = bound.into_inner().unwrap_err();
+        FakeCoresImpl::set_core_index(0);
+        assert_eq!(*bound.into_inner().unwrap().borrow(), 43);
+    }
+}
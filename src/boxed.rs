@@ -2,30 +2,30 @@
 // This project is dual-licensed under Apache 2.0 and MIT terms.
 // See LICENSE-APACHE and LICENSE-MIT for details.
 
-use crate::{Cores, ExceptionLock, PerCore};
+use crate::{ExecutionContext, PerCore};
 use alloc::boxed::Box;
 use core::iter::repeat_with;
 
-// SAFETY: Both different CPU cores and different exception contexts must be treated as separate
-// 'threads' for the purposes of Rust's memory model. `PerCore` only allows access to the value for
-// the current core, and `ExceptionLock` requires exceptions to be disabled while accessing it which
-// prevents concurrent access to its contents from different exception contexts. The combination of
-// the two therefore prevents concurrent access to `T`.
-unsafe impl<V: Send, C: Cores> Sync for PerCore<Box<[ExceptionLock<V>]>, C> {}
-
-impl<T, C: Cores> PerCore<Box<[T]>, C> {
-    /// Gets a shared reference to the value for the current CPU core.
-    pub fn get(&self) -> &T {
-        &self.values[C::core_index()]
+impl<T, C: ExecutionContext> PerCore<Box<[T]>, C> {
+    /// Returns a new `PerCore` wrapping a boxed slice of `core_count` elements, each initialised by
+    /// calling `f` with the core's index.
+    pub fn new_with(core_count: usize, f: impl FnMut(usize) -> T) -> Self {
+        Self::new((0..core_count).map(f).collect())
     }
 
-    /// Gets a unique reference to the value for the current CPU core.
-    pub fn get_mut(&mut self) -> &mut T {
-        &mut self.values[C::core_index()]
+    /// Returns a new `PerCore` wrapping a boxed slice of `core_count` elements, each initialised by
+    /// calling `f` with the core's index.
+    ///
+    /// Returns the first error returned by `f`, without calling it for any later cores.
+    pub fn try_new_with<E>(
+        core_count: usize,
+        f: impl FnMut(usize) -> Result<T, E>,
+    ) -> Result<Self, E> {
+        Ok(Self::new((0..core_count).map(f).collect::<Result<_, E>>()?))
     }
 }
 
-impl<T: Default, C: Cores> PerCore<Box<[T]>, C> {
+impl<T: Default, C: ExecutionContext> PerCore<Box<[T]>, C> {
     /// Returns a new `PerCore` wrapping a boxed slice of `core_count` elements, each initialised to
     /// the default value of `T`.
     pub fn new_with_default(core_count: usize) -> Self {
@@ -39,9 +39,13 @@ impl<T: Default, C: Cores> PerCore<Box<[T]>, C> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{ExceptionFree, tests::FakeCoresImpl};
+    use crate::{ExceptionFree, ExceptionLock, tests::FakeCoresImpl};
     use alloc::boxed::Box;
-    use core::{cell::RefCell, iter::repeat_with};
+    use core::{
+        cell::RefCell,
+        iter::repeat_with,
+        sync::atomic::{AtomicU64, Ordering},
+    };
     use spin::{LazyLock, once::Once};
 
     type BoxedSlice = Box<[ExceptionLock<RefCell<u32>>]>;
@@ -84,4 +88,39 @@ mod tests {
             assert_eq!(*STATE.get().borrow_mut(token), 1);
         }
     }
+
+    #[test]
+    fn percore_boxed_slice_new_with() {
+        let values = PerCore::<Box<[u64]>, FakeCoresImpl>::new_with(3, |core_index| {
+            core_index as u64 * 0x1000
+        });
+        FakeCoresImpl::set_core_index(2);
+        assert_eq!(*values.get(), 0x2000);
+
+        let mut calls = 0;
+        let result = PerCore::<Box<[u64]>, FakeCoresImpl>::try_new_with(3, |core_index| {
+            calls += 1;
+            if core_index == 1 {
+                Err("no memory")
+            } else {
+                Ok(0)
+            }
+        });
+        assert_eq!(result.err(), Some("no memory"));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn percore_boxed_slice_sum() {
+        let counts = PerCore::<Box<[AtomicU64]>, FakeCoresImpl>::new_with_default(3);
+        for (core_index, count) in counts.iter().enumerate() {
+            count.store(core_index as u64 * 10, Ordering::Relaxed);
+        }
+
+        assert_eq!(counts.get_for(1).unwrap().load(Ordering::Relaxed), 10);
+        assert_eq!(
+            counts.sum::<u64, _>(|count| count.load(Ordering::Relaxed)),
+            30
+        );
+    }
 }
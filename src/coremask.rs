@@ -0,0 +1,448 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{Cores, ExecutionContext, PerCore, Storage};
+use core::{
+    iter::FusedIterator,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// A set of CPU core indices, stored as a bitmap of `WORDS` 64-bit words.
+///
+/// The set can contain core indices up to `64 * WORDS - 1`. Methods which take a core index panic
+/// if it is outside this range.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CoreMask<const WORDS: usize = 1> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> CoreMask<WORDS> {
+    /// The number of core indices which the set can contain.
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    /// Returns an empty set.
+    pub const fn empty() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    /// Returns a set containing the cores with indices `0..core_count`.
+    ///
+    /// Panics if `core_count` is greater than `CAPACITY`.
+    pub const fn first(core_count: usize) -> Self {
+        assert!(core_count <= Self::CAPACITY);
+        let mut words = [0; WORDS];
+        let mut i = 0;
+        while i < WORDS {
+            let bits = core_count.saturating_sub(i * 64);
+            words[i] = if bits >= 64 {
+                u64::MAX
+            } else {
+                (1 << bits) - 1
+            };
+            i += 1;
+        }
+        Self { words }
+    }
+
+    /// Returns a set containing only the core with the given index.
+    pub const fn single(core_index: usize) -> Self {
+        let mut mask = Self::empty();
+        mask.words[core_index / 64] = 1 << (core_index % 64);
+        mask
+    }
+
+    /// Returns a set containing only the current core.
+    pub fn current<C: Cores>() -> Self {
+        Self::single(C::core_index())
+    }
+
+    /// Adds the given core to the set, returning whether it was newly added.
+    pub fn insert(&mut self, core_index: usize) -> bool {
+        let was_present = self.contains(core_index);
+        self.words[core_index / 64] |= 1 << (core_index % 64);
+        !was_present
+    }
+
+    /// Removes the given core from the set, returning whether it was present.
+    pub fn remove(&mut self, core_index: usize) -> bool {
+        let was_present = self.contains(core_index);
+        self.words[core_index / 64] &= !(1 << (core_index % 64));
+        was_present
+    }
+
+    /// Returns whether the set contains the given core.
+    pub const fn contains(&self, core_index: usize) -> bool {
+        self.words[core_index / 64] & (1 << (core_index % 64)) != 0
+    }
+
+    /// Returns whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Returns the number of cores in the set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the set of cores in either `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_words(other, |a, b| a | b)
+    }
+
+    /// Returns the set of cores in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_words(other, |a, b| a & b)
+    }
+
+    /// Returns the set of cores in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_words(other, |a, b| a & !b)
+    }
+
+    /// Returns whether every core in `self` is also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Returns an iterator over the indices of the cores in the set, in ascending order.
+    pub fn iter(&self) -> CoreMaskIter<WORDS> {
+        CoreMaskIter {
+            mask: *self,
+            word_index: 0,
+        }
+    }
+
+    /// Returns the underlying bitmap words, least significant first.
+    pub const fn as_words(&self) -> &[u64; WORDS] {
+        &self.words
+    }
+
+    /// Returns a set with the given bitmap words, least significant first.
+    pub const fn from_words(words: [u64; WORDS]) -> Self {
+        Self { words }
+    }
+
+    fn zip_words(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        let mut words = self.words;
+        for (word, other_word) in words.iter_mut().zip(other.words) {
+            *word = f(*word, other_word);
+        }
+        Self { words }
+    }
+}
+
+impl<const WORDS: usize> Default for CoreMask<WORDS> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<const WORDS: usize> BitOr for CoreMask<WORDS> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(&rhs)
+    }
+}
+
+impl<const WORDS: usize> BitOrAssign for CoreMask<WORDS> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(&rhs);
+    }
+}
+
+impl<const WORDS: usize> BitAnd for CoreMask<WORDS> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(&rhs)
+    }
+}
+
+impl<const WORDS: usize> BitAndAssign for CoreMask<WORDS> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = self.intersection(&rhs);
+    }
+}
+
+impl<const WORDS: usize> Sub for CoreMask<WORDS> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.difference(&rhs)
+    }
+}
+
+impl<const WORDS: usize> SubAssign for CoreMask<WORDS> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.difference(&rhs);
+    }
+}
+
+impl<const WORDS: usize> Not for CoreMask<WORDS> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self {
+            words: self.words.map(|word| !word),
+        }
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for CoreMask<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut mask = Self::empty();
+        for core_index in iter {
+            mask.insert(core_index);
+        }
+        mask
+    }
+}
+
+impl<const WORDS: usize> IntoIterator for CoreMask<WORDS> {
+    type Item = usize;
+    type IntoIter = CoreMaskIter<WORDS>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<const WORDS: usize> IntoIterator for &CoreMask<WORDS> {
+    type Item = usize;
+    type IntoIter = CoreMaskIter<WORDS>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the core indices in a [`CoreMask`], in ascending order.
+#[derive(Clone, Debug)]
+pub struct CoreMaskIter<const WORDS: usize> {
+    /// The cores which haven't been returned yet.
+    mask: CoreMask<WORDS>,
+    /// The index of the first word of `mask` which may be non-zero.
+    word_index: usize,
+}
+
+impl<const WORDS: usize> Iterator for CoreMaskIter<WORDS> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word_index < WORDS {
+            let word = &mut self.mask.words[self.word_index];
+            if *word != 0 {
+                let bit = word.trailing_zeros() as usize;
+                *word &= *word - 1;
+                return Some(self.word_index * 64 + bit);
+            }
+            self.word_index += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.mask.len();
+        (len, Some(len))
+    }
+}
+
+impl<const WORDS: usize> ExactSizeIterator for CoreMaskIter<WORDS> {}
+
+impl<const WORDS: usize> FusedIterator for CoreMaskIter<WORDS> {}
+
+/// A set of CPU core indices which can be safely shared and modified between cores.
+///
+/// Each word is updated atomically, but operations on different words are not atomic with respect
+/// to each other, so [`load`](Self::load) may observe a mixture of old and new values if there are
+/// concurrent modifications to cores in different words.
+#[derive(Debug)]
+pub struct AtomicCoreMask<const WORDS: usize = 1> {
+    words: [AtomicU64; WORDS],
+}
+
+impl<const WORDS: usize> AtomicCoreMask<WORDS> {
+    /// Creates a new atomic set with the given initial contents.
+    pub const fn new(mask: CoreMask<WORDS>) -> Self {
+        let mut words = [const { AtomicU64::new(0) }; WORDS];
+        let mut i = 0;
+        while i < WORDS {
+            words[i] = AtomicU64::new(mask.words[i]);
+            i += 1;
+        }
+        Self { words }
+    }
+
+    /// Returns the current contents of the set.
+    pub fn load(&self, ordering: Ordering) -> CoreMask<WORDS> {
+        let mut words = [0; WORDS];
+        for (word, atomic_word) in words.iter_mut().zip(&self.words) {
+            *word = atomic_word.load(ordering);
+        }
+        CoreMask { words }
+    }
+
+    /// Replaces the contents of the set.
+    pub fn store(&self, mask: CoreMask<WORDS>, ordering: Ordering) {
+        for (atomic_word, word) in self.words.iter().zip(mask.words) {
+            atomic_word.store(word, ordering);
+        }
+    }
+
+    /// Adds the given core to the set, returning whether it was newly added.
+    pub fn insert(&self, core_index: usize, ordering: Ordering) -> bool {
+        let bit = 1 << (core_index % 64);
+        self.words[core_index / 64].fetch_or(bit, ordering) & bit == 0
+    }
+
+    /// Removes the given core from the set, returning whether it was present.
+    pub fn remove(&self, core_index: usize, ordering: Ordering) -> bool {
+        let bit = 1 << (core_index % 64);
+        self.words[core_index / 64].fetch_and(!bit, ordering) & bit != 0
+    }
+
+    /// Returns whether the set contains the given core.
+    pub fn contains(&self, core_index: usize, ordering: Ordering) -> bool {
+        self.words[core_index / 64].load(ordering) & (1 << (core_index % 64)) != 0
+    }
+
+    /// Consumes the atomic set, returning its contents.
+    pub fn into_inner(self) -> CoreMask<WORDS> {
+        CoreMask {
+            words: self.words.map(AtomicU64::into_inner),
+        }
+    }
+}
+
+impl<const WORDS: usize> Default for AtomicCoreMask<WORDS> {
+    fn default() -> Self {
+        Self::new(CoreMask::empty())
+    }
+}
+
+impl<const WORDS: usize> From<CoreMask<WORDS>> for AtomicCoreMask<WORDS> {
+    fn from(mask: CoreMask<WORDS>) -> Self {
+        Self::new(mask)
+    }
+}
+
+impl<V: Storage, C: ExecutionContext> PerCore<V, C> {
+    /// Returns an iterator over the index and value of each core in the given mask.
+    ///
+    /// Cores in the mask which don't have a value are skipped. This is only available for values
+    /// which can be safely accessed from any core.
+    pub fn iter_mask<const WORDS: usize>(
+        &self,
+        mask: CoreMask<WORDS>,
+    ) -> impl Iterator<Item = (usize, &V::Item)>
+    where
+        V::Item: Sync,
+    {
+        let values = self.values.as_slice();
+        mask.into_iter()
+            .map_while(move |core_index| Some((core_index, values.get(core_index)?)))
+    }
+
+    /// Returns an iterator over the index and a unique reference to the value of each core in the
+    /// given mask.
+    ///
+    /// Cores in the mask which don't have a value are skipped.
+    pub fn iter_mask_mut<const WORDS: usize>(
+        &mut self,
+        mask: CoreMask<WORDS>,
+    ) -> impl Iterator<Item = (usize, &mut V::Item)> {
+        self.values
+            .as_mut_slice()
+            .iter_mut()
+            .enumerate()
+            .filter(move |(core_index, _)| {
+                *core_index < CoreMask::<WORDS>::CAPACITY && mask.contains(*core_index)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use core::sync::atomic::AtomicU32;
+
+    #[test]
+    fn set_operations() {
+        let a: CoreMask<2> = [0, 3, 64, 100].into_iter().collect();
+        let b = CoreMask::<2>::first(4);
+        assert_eq!(b.iter().collect::<Vec<_>>(), [0, 1, 2, 3]);
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), [0, 1, 2, 3, 64, 100]);
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), [0, 3]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), [64, 100]);
+        assert_eq!((!a).len(), 124);
+        assert!((a & b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert_eq!(a.iter().len(), 4);
+        assert_eq!(CoreMask::<2>::first(128), !CoreMask::empty());
+
+        let mut c = CoreMask::<1>::empty();
+        assert!(c.is_empty());
+        assert!(c.insert(5));
+        assert!(!c.insert(5));
+        assert!(c.contains(5));
+        assert!(c.remove(5));
+        assert!(!c.remove(5));
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn current() {
+        FakeCoresImpl::set_core_index(2);
+        assert_eq!(
+            CoreMask::<1>::current::<FakeCoresImpl>(),
+            CoreMask::single(2)
+        );
+    }
+
+    #[test]
+    fn atomic() {
+        let mask = AtomicCoreMask::<2>::default();
+        assert!(mask.insert(70, Ordering::Relaxed));
+        assert!(!mask.insert(70, Ordering::Relaxed));
+        assert!(mask.insert(1, Ordering::Relaxed));
+        assert!(mask.contains(70, Ordering::Relaxed));
+        assert_eq!(
+            mask.load(Ordering::Relaxed).iter().collect::<Vec<_>>(),
+            [1, 70]
+        );
+        assert!(mask.remove(1, Ordering::Relaxed));
+        assert!(!mask.contains(1, Ordering::Relaxed));
+        assert_eq!(mask.into_inner(), CoreMask::single(70));
+    }
+
+    #[test]
+    fn percore_iter_mask() {
+        let mut values = PerCore::<_, FakeCoresImpl>::new([
+            AtomicU32::new(10),
+            AtomicU32::new(11),
+            AtomicU32::new(12),
+        ]);
+        let mask: CoreMask = [0, 2, 5].into_iter().collect();
+
+        assert_eq!(
+            values
+                .iter_mask(mask)
+                .map(|(core_index, value)| (core_index, value.load(Ordering::Relaxed)))
+                .collect::<Vec<_>>(),
+            [(0, 10), (2, 12)]
+        );
+
+        for (_, value) in values.iter_mask_mut(mask) {
+            *value.get_mut() += 1;
+        }
+        assert_eq!(values.into_inner().map(AtomicU32::into_inner), [11, 11, 13]);
+    }
+}
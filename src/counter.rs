@@ -0,0 +1,359 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CachePadded, CoreSync, ExecutionContext, PerCore, Topology, TopologyLevel};
+#[cfg(target_has_atomic = "32")]
+use core::sync::atomic::AtomicU32;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64;
+use core::{marker::PhantomData, sync::atomic::Ordering};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer type which can be used as the value of a [`CounterCell`].
+///
+/// This is implemented for `u32` and `u64`, on targets which support atomic operations on them.
+pub trait CounterValue: sealed::Sealed + Copy {
+    /// The atomic type used to store the value.
+    #[doc(hidden)]
+    type Atomic: Sync;
+
+    /// An atomic with the value zero.
+    #[doc(hidden)]
+    // Each use of the constant creates a new atomic, which is what we want.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ATOMIC_ZERO: Self::Atomic;
+
+    /// The value zero.
+    #[doc(hidden)]
+    const ZERO: Self;
+
+    #[doc(hidden)]
+    fn load(atomic: &Self::Atomic) -> Self;
+
+    #[doc(hidden)]
+    fn store(atomic: &Self::Atomic, value: Self);
+
+    #[doc(hidden)]
+    fn fetch_add(atomic: &Self::Atomic, value: Self);
+
+    #[doc(hidden)]
+    fn fetch_saturating_add(atomic: &Self::Atomic, value: Self);
+
+    #[doc(hidden)]
+    fn wrapping_add(self, other: Self) -> Self;
+
+    #[doc(hidden)]
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+macro_rules! impl_counter_value {
+    ($width:literal, $type:ty, $atomic:ty) => {
+        #[cfg(target_has_atomic = $width)]
+        impl sealed::Sealed for $type {}
+
+        #[cfg(target_has_atomic = $width)]
+        impl CounterValue for $type {
+            type Atomic = $atomic;
+
+            #[allow(clippy::declare_interior_mutable_const)]
+            const ATOMIC_ZERO: $atomic = <$atomic>::new(0);
+
+            const ZERO: Self = 0;
+
+            fn load(atomic: &$atomic) -> Self {
+                atomic.load(Ordering::Relaxed)
+            }
+
+            fn store(atomic: &$atomic, value: Self) {
+                atomic.store(value, Ordering::Relaxed);
+            }
+
+            fn fetch_add(atomic: &$atomic, value: Self) {
+                atomic.fetch_add(value, Ordering::Relaxed);
+            }
+
+            fn fetch_saturating_add(atomic: &$atomic, value: Self) {
+                // The closure always returns `Some`, so this can't fail.
+                let _ = atomic.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |old| {
+                    Some(old.saturating_add(value))
+                });
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+
+            fn saturating_add(self, other: Self) -> Self {
+                self.saturating_add(other)
+            }
+        }
+    };
+}
+
+impl_counter_value!("32", u32, AtomicU32);
+impl_counter_value!("64", u64, AtomicU64);
+
+/// How a counter behaves when it overflows: either [`Wrap`] or [`Saturate`].
+pub trait CounterMode: sealed::Sealed {
+    #[doc(hidden)]
+    fn add_atomic<T: CounterValue>(atomic: &T::Atomic, value: T);
+
+    #[doc(hidden)]
+    fn add<T: CounterValue>(a: T, b: T) -> T;
+}
+
+/// Counter mode which wraps around to zero on overflow.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Wrap;
+
+impl sealed::Sealed for Wrap {}
+
+impl CounterMode for Wrap {
+    fn add_atomic<T: CounterValue>(atomic: &T::Atomic, value: T) {
+        T::fetch_add(atomic, value);
+    }
+
+    fn add<T: CounterValue>(a: T, b: T) -> T {
+        a.wrapping_add(b)
+    }
+}
+
+/// Counter mode which stays at the maximum value on overflow.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Saturate;
+
+impl sealed::Sealed for Saturate {}
+
+impl CounterMode for Saturate {
+    fn add_atomic<T: CounterValue>(atomic: &T::Atomic, value: T) {
+        T::fetch_saturating_add(atomic, value);
+    }
+
+    fn add<T: CounterValue>(a: T, b: T) -> T {
+        a.saturating_add(b)
+    }
+}
+
+/// A single core's slot of a per-core counter.
+///
+/// The slot is padded to a cache line, so that incrementing one core's counter doesn't contend with
+/// other cores' counters. The value is updated with relaxed atomic operations, so it can be
+/// incremented from exception handlers without masking exceptions, and read from any core.
+///
+/// This is usually used through [`PerCoreCounter`], but can also be used directly as a
+/// `#[percore]` variable.
+pub struct CounterCell<T: CounterValue, M: CounterMode = Wrap> {
+    value: CachePadded<T::Atomic>,
+    _mode: PhantomData<fn() -> M>,
+}
+
+impl<T: CounterValue, M: CounterMode> CounterCell<T, M> {
+    /// Creates a new counter slot with the value zero.
+    pub const fn new() -> Self {
+        Self {
+            value: CachePadded::new(T::ATOMIC_ZERO),
+            _mode: PhantomData,
+        }
+    }
+
+    /// Adds the given amount to the counter.
+    pub fn add(&self, amount: T) {
+        M::add_atomic::<T>(&self.value, amount);
+    }
+
+    /// Returns the current value of the counter.
+    pub fn get(&self) -> T {
+        T::load(&self.value)
+    }
+
+    /// Resets the counter to zero.
+    pub fn reset(&self) {
+        T::store(&self.value, T::ZERO);
+    }
+}
+
+impl<T: CounterValue, M: CounterMode> Default for CounterCell<T, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SAFETY: The value is only accessed with atomic operations, so it can be shared between exception
+// contexts as well as cores.
+unsafe impl<T: CounterValue, M: CounterMode> CoreSync for CounterCell<T, M> {}
+
+/// Sums the values of the given counter slots, according to the mode of the counter.
+fn sum_cells<'a, T: CounterValue + 'a, M: CounterMode + 'a>(
+    cells: impl IntoIterator<Item = &'a CounterCell<T, M>>,
+) -> T {
+    cells
+        .into_iter()
+        .fold(T::ZERO, |sum, cell| M::add(sum, cell.get()))
+}
+
+/// A counter with a separate slot for each CPU core, such as for counting packets or events.
+///
+/// Adding to the counter only touches the current core's slot, so cores never contend with each
+/// other. Reading the total with [`sum`](Self::sum) reads every core's slot. The counter may either
+/// [`Wrap`] (the default) or [`Saturate`] on overflow; in either case the sum does the same.
+///
+/// Because each core's slot is updated separately, the sum is not an atomic snapshot: increments
+/// which happen concurrently with a call to `sum` may or may not be included.
+pub struct PerCoreCounter<
+    T: CounterValue,
+    const CORE_COUNT: usize,
+    C: ExecutionContext,
+    M: CounterMode = Wrap,
+> {
+    cells: PerCore<[CounterCell<T, M>; CORE_COUNT], C>,
+}
+
+impl<T: CounterValue, const CORE_COUNT: usize, C: ExecutionContext, M: CounterMode>
+    PerCoreCounter<T, CORE_COUNT, C, M>
+{
+    /// Creates a new counter with the value zero on every core.
+    pub const fn new() -> Self {
+        Self {
+            cells: PerCore::new([const { CounterCell::new() }; CORE_COUNT]),
+        }
+    }
+
+    /// Adds the given amount to the current core's slot.
+    pub fn add(&self, amount: T) {
+        self.cells.get().add(amount);
+    }
+
+    /// Returns the value of the current core's slot.
+    pub fn get(&self) -> T {
+        self.cells.get().get()
+    }
+
+    /// Returns the value of the slot for the core with the given index, or `None` if there is no such
+    /// core.
+    pub fn get_for(&self, core_index: usize) -> Option<T> {
+        self.cells.get_for(core_index).map(CounterCell::get)
+    }
+
+    /// Returns the sum of all cores' slots.
+    pub fn sum(&self) -> T {
+        sum_cells(self.cells.iter())
+    }
+
+    /// Returns the sum of the slots of all cores in the group with the given index at topology
+    /// level `L`, e.g. all cores in a cluster.
+    pub fn sum_group<L: TopologyLevel>(&self, group_index: usize) -> T
+    where
+        C: Topology<L>,
+    {
+        sum_cells(
+            self.cells
+                .iter_group::<L>(group_index)
+                .map(|(_, cell)| cell),
+        )
+    }
+
+    /// Resets every core's slot to zero.
+    ///
+    /// Increments which happen concurrently on other cores may or may not be lost.
+    pub fn reset(&self) {
+        self.cells.iter().for_each(CounterCell::reset);
+    }
+}
+
+impl<const CORE_COUNT: usize, C: ExecutionContext, M: CounterMode>
+    PerCoreCounter<u32, CORE_COUNT, C, M>
+{
+    /// Adds one to the current core's slot.
+    pub fn increment(&self) {
+        self.add(1);
+    }
+}
+
+impl<const CORE_COUNT: usize, C: ExecutionContext, M: CounterMode>
+    PerCoreCounter<u64, CORE_COUNT, C, M>
+{
+    /// Adds one to the current core's slot.
+    pub fn increment(&self) {
+        self.add(1);
+    }
+}
+
+impl<T: CounterValue, const CORE_COUNT: usize, C: ExecutionContext, M: CounterMode> Default
+    for PerCoreCounter<T, CORE_COUNT, C, M>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "derive")]
+impl<T: CounterValue, M: CounterMode> crate::derive::LinkedPerCore<CounterCell<T, M>> {
+    /// Returns the sum of the counter's slots in the percore areas at each of the given offsets.
+    ///
+    /// # Safety
+    ///
+    /// Each offset must be the offset of a valid and initialised percore area from the `percore`
+    /// section, as would be returned by
+    /// [`PercoreLocalOffset`](crate::derive::PercoreLocalOffset) on some core.
+    pub unsafe fn sum(&self, offsets: impl IntoIterator<Item = isize>) -> T {
+        sum_cells(offsets.into_iter().map(|offset| {
+            // SAFETY: Our caller promises that the offset is valid.
+            unsafe { self.get_at_offset(offset) }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use std::thread;
+
+    #[test]
+    fn sum_across_cores() {
+        static PACKETS: PerCoreCounter<u64, 4, FakeCoresImpl> = PerCoreCounter::new();
+
+        for core_index in 0..4 {
+            thread::spawn(move || {
+                FakeCoresImpl::set_core_index(core_index);
+                for _ in 0..=core_index {
+                    PACKETS.increment();
+                }
+                PACKETS.add(10);
+            })
+            .join()
+            .unwrap();
+        }
+
+        assert_eq!(PACKETS.get_for(3), Some(14));
+        assert_eq!(PACKETS.get_for(4), None);
+        assert_eq!(PACKETS.sum(), 50);
+
+        PACKETS.reset();
+        assert_eq!(PACKETS.sum(), 0);
+    }
+
+    #[test]
+    fn overflow_modes() {
+        let wrapping = PerCoreCounter::<u32, 2, FakeCoresImpl>::new();
+        let saturating = PerCoreCounter::<u32, 2, FakeCoresImpl, Saturate>::new();
+
+        FakeCoresImpl::set_core_index(0);
+        wrapping.add(u32::MAX);
+        wrapping.increment();
+        saturating.add(u32::MAX);
+        saturating.increment();
+        assert_eq!(wrapping.get(), 0);
+        assert_eq!(saturating.get(), u32::MAX);
+
+        FakeCoresImpl::set_core_index(1);
+        wrapping.add(u32::MAX);
+        saturating.add(1);
+        assert_eq!(wrapping.sum(), u32::MAX);
+        assert_eq!(saturating.sum(), u32::MAX);
+    }
+}
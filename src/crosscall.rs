@@ -0,0 +1,471 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{Cores, ExceptionFree, PerCoreMailbox};
+#[cfg(target_arch = "aarch64")]
+use core::{arch::asm, marker::PhantomData};
+use core::{
+    fmt::{self, Display, Formatter},
+    hint::spin_loop,
+    mem::transmute,
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A platform mechanism for raising inter-processor interrupts (IPIs).
+pub trait Ipi {
+    /// Raises an IPI on the core with the given index.
+    ///
+    /// Any memory writes made before this is called must be visible to the target core by the time
+    /// it handles the IPI.
+    fn raise(&self, core_index: usize);
+}
+
+/// Maps core indices to the corresponding MPIDR affinity values.
+pub trait CoreAffinity {
+    /// Returns the MPIDR value of the core with the given index.
+    ///
+    /// Only the affinity fields are used, other bits are ignored.
+    fn mpidr(core_index: usize) -> u64;
+}
+
+/// Raises IPIs as GICv3 software-generated interrupts (SGIs) to the current security state, by
+/// writing to `ICC_SGI1R_EL1`.
+#[cfg(target_arch = "aarch64")]
+pub struct GicV3Sgi<A: CoreAffinity> {
+    intid: u8,
+    _affinity: PhantomData<A>,
+}
+
+#[cfg(target_arch = "aarch64")]
+impl<A: CoreAffinity> GicV3Sgi<A> {
+    /// Creates a new `GicV3Sgi` which raises the SGI with the given interrupt ID.
+    ///
+    /// Panics if `intid` is not a valid SGI ID, i.e. less than 16.
+    pub const fn new(intid: u8) -> Self {
+        assert!(intid < 16);
+        Self {
+            intid,
+            _affinity: PhantomData,
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl<A: CoreAffinity> Ipi for GicV3Sgi<A> {
+    fn raise(&self, core_index: usize) {
+        let value = sgi1r_value(A::mpidr(core_index), self.intid);
+        // SAFETY: Writing to ICC_SGI1R_EL1 just raises an SGI, it doesn't access memory. The barrier
+        // ensures that previous memory writes are visible to the target core before it receives the
+        // SGI.
+        unsafe {
+            asm!(
+                "dsb ishst",
+                "msr icc_sgi1r_el1, {value}",
+                "isb",
+                options(nostack),
+                value = in(reg) value,
+            );
+        }
+    }
+}
+
+/// Returns the value to write to `ICC_SGI1R_EL1` to send the given SGI to the core with the given
+/// MPIDR value.
+#[cfg(any(target_arch = "aarch64", test))]
+fn sgi1r_value(mpidr: u64, intid: u8) -> u64 {
+    let aff0 = mpidr & 0xff;
+    let aff1 = (mpidr >> 8) & 0xff;
+    let aff2 = (mpidr >> 16) & 0xff;
+    let aff3 = (mpidr >> 32) & 0xff;
+    (1 << (aff0 & 0xf))
+        | (aff1 << 16)
+        | (u64::from(intid & 0xf) << 24)
+        | (aff2 << 32)
+        | ((aff0 >> 4) << 44)
+        | (aff3 << 48)
+}
+
+/// The destination core's inbox was full, so a call couldn't be sent.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InboxFull;
+
+impl Display for InboxFull {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Destination core's cross-call inbox is full")
+    }
+}
+
+impl core::error::Error for InboxFull {}
+
+/// A function to be called on another core.
+struct Call {
+    function: NonNull<dyn Fn(ExceptionFree<'_>) + Sync>,
+    /// Counter to decrement once the function has returned, if the caller is waiting for it.
+    pending: Option<NonNull<AtomicUsize>>,
+}
+
+// SAFETY: The function is `Sync`, so it may be called from any core, and the counter is atomic. The
+// sender ensures that both remain valid until the call has finished.
+unsafe impl Send for Call {}
+
+/// Runs functions on other cores, by sending them via a [`PerCoreMailbox`] and raising an IPI.
+///
+/// The platform's IPI handler must call [`handle_ipi`](Self::handle_ipi) to run the functions
+/// which have been sent to the current core. Each core's inbox can hold up to `CAP` calls.
+pub struct CrossCall<I: Ipi, const CAP: usize, const CORE_COUNT: usize, C: Cores> {
+    mailbox: PerCoreMailbox<Call, CAP, CORE_COUNT, C>,
+    ipi: I,
+}
+
+impl<I: Ipi, const CAP: usize, const CORE_COUNT: usize, C: Cores> CrossCall<I, CAP, CORE_COUNT, C> {
+    /// Creates a new `CrossCall` which uses the given IPI mechanism.
+    pub const fn new(ipi: I) -> Self {
+        Self {
+            mailbox: PerCoreMailbox::new(),
+            ipi,
+        }
+    }
+
+    /// Returns the IPI mechanism used to notify other cores.
+    pub fn ipi(&self) -> &I {
+        &self.ipi
+    }
+
+    /// Runs all functions which have been sent to the current core.
+    ///
+    /// This should be called from the IPI handler.
+    pub fn handle_ipi(&self, token: ExceptionFree) {
+        while let Some(call) = self.mailbox.receive(token) {
+            // SAFETY: The sender ensures that the function remains valid until we decrement the
+            // pending counter, or forever if there is no counter.
+            unsafe {
+                call.function.as_ref()(token);
+            }
+            if let Some(pending) = call.pending {
+                // SAFETY: The sender ensures that the counter remains valid until it reaches zero.
+                unsafe {
+                    pending.as_ref().fetch_sub(1, Ordering::Release);
+                }
+            }
+        }
+    }
+
+    /// Sends the given function to be run on the core with the given index, without waiting for
+    /// it to finish.
+    ///
+    /// Returns an error if the destination core's inbox is full.
+    ///
+    /// Panics if `core_index` is not less than `CORE_COUNT`.
+    pub fn post(
+        &self,
+        token: ExceptionFree,
+        core_index: usize,
+        function: &'static (dyn Fn(ExceptionFree<'_>) + Sync),
+    ) -> Result<(), InboxFull> {
+        let call = Call {
+            function: NonNull::from(function),
+            pending: None,
+        };
+        self.mailbox
+            .send(token, core_index, call)
+            .map_err(|_| InboxFull)?;
+        self.ipi.raise(core_index);
+        Ok(())
+    }
+
+    /// Runs the given function on the core with the given index, and waits for it to finish.
+    ///
+    /// If the destination is the current core then the function is run directly. While waiting,
+    /// functions sent to the current core by other cores are run, so it is safe for two cores to
+    /// call each other at the same time.
+    ///
+    /// Panics if `core_index` is not less than `CORE_COUNT`.
+    pub fn run_on_core(
+        &self,
+        token: ExceptionFree,
+        core_index: usize,
+        function: impl Fn(ExceptionFree<'_>) + Sync,
+    ) {
+        assert!(core_index < CORE_COUNT);
+        if core_index == C::core_index() {
+            function(token);
+            return;
+        }
+
+        // Declared after `function`, so that it waits for the call to finish before `function` is
+        // dropped, even if sending panics.
+        let pending = PendingGuard::new(self, token);
+        // SAFETY: `pending` waits until the counter reaches zero before it is dropped.
+        unsafe {
+            self.send_waiting(token, core_index, &function, &pending.count);
+        }
+    }
+
+    /// Runs the given function on every core, including the current one, and waits for them all to
+    /// finish.
+    ///
+    /// The function is run on the current core first, before being sent to the other cores.
+    pub fn run_on_all_cores(
+        &self,
+        token: ExceptionFree,
+        function: impl Fn(ExceptionFree<'_>) + Sync,
+    ) {
+        let current_core = C::core_index();
+        function(token);
+
+        // Declared after `function`, so that it waits for all calls to finish before `function` is
+        // dropped, even if sending panics part way through.
+        let pending = PendingGuard::new(self, token);
+        for core_index in (0..CORE_COUNT).filter(|&core_index| core_index != current_core) {
+            // SAFETY: `pending` waits until the counter reaches zero before it is dropped.
+            unsafe {
+                self.send_waiting(token, core_index, &function, &pending.count);
+            }
+        }
+    }
+
+    /// Sends the given function to the given core, retrying until there is space in its inbox.
+    ///
+    /// `pending` is incremented while the call is in the destination's inbox, and decremented again
+    /// once the function has returned.
+    ///
+    /// # Safety
+    ///
+    /// The caller must not allow `function` or `pending` to be dropped until `pending` reaches zero.
+    unsafe fn send_waiting(
+        &self,
+        token: ExceptionFree,
+        core_index: usize,
+        function: &(dyn Fn(ExceptionFree<'_>) + Sync),
+        pending: &AtomicUsize,
+    ) {
+        // SAFETY: This only extends the lifetime of the trait object, and our caller promises to keep
+        // it alive until it is no longer used.
+        let function = unsafe {
+            transmute::<
+                &(dyn Fn(ExceptionFree<'_>) + Sync),
+                &'static (dyn Fn(ExceptionFree<'_>) + Sync),
+            >(function)
+        };
+        let mut call = Call {
+            function: NonNull::from(function),
+            pending: Some(NonNull::from(pending)),
+        };
+        // Count the call before sending it, so that the destination can't decrement the counter
+        // first. If the send fails the call is handed back to us, so it no longer needs counting.
+        pending.fetch_add(1, Ordering::Relaxed);
+        while let Err(returned_call) = self.mailbox.send(token, core_index, call) {
+            pending.fetch_sub(1, Ordering::Relaxed);
+            call = returned_call;
+            // Run our own calls while waiting, in case the destination is waiting for us.
+            self.handle_ipi(token);
+            spin_loop();
+            pending.fetch_add(1, Ordering::Relaxed);
+        }
+        self.ipi.raise(core_index);
+    }
+
+    /// Waits until the given counter reaches zero, running calls sent to the current core in the
+    /// meantime.
+    fn wait(&self, token: ExceptionFree, pending: &AtomicUsize) {
+        while pending.load(Ordering::Acquire) != 0 {
+            self.handle_ipi(token);
+            spin_loop();
+        }
+    }
+}
+
+/// Counts the calls sent by [`CrossCall::run_on_core`] or [`CrossCall::run_on_all_cores`] which
+/// haven't finished yet, and waits for them all to finish when dropped.
+///
+/// Waiting on drop rather than at the end of the function ensures that other cores can't be left
+/// calling a function which has been freed if the sender panics part way through.
+struct PendingGuard<'a, I: Ipi, const CAP: usize, const CORE_COUNT: usize, C: Cores> {
+    cross_call: &'a CrossCall<I, CAP, CORE_COUNT, C>,
+    token: ExceptionFree<'a>,
+    count: AtomicUsize,
+}
+
+impl<'a, I: Ipi, const CAP: usize, const CORE_COUNT: usize, C: Cores>
+    PendingGuard<'a, I, CAP, CORE_COUNT, C>
+{
+    fn new(cross_call: &'a CrossCall<I, CAP, CORE_COUNT, C>, token: ExceptionFree<'a>) -> Self {
+        Self {
+            cross_call,
+            token,
+            count: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<I: Ipi, const CAP: usize, const CORE_COUNT: usize, C: Cores> Drop
+    for PendingGuard<'_, I, CAP, CORE_COUNT, C>
+{
+    fn drop(&mut self) {
+        self.cross_call.wait(self.token, &self.count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use core::sync::atomic::AtomicBool;
+    use std::{
+        panic::{AssertUnwindSafe, catch_unwind},
+        thread,
+    };
+
+    /// A fake implementation of [`Ipi`] for tests on the host, which records which cores have pending
+    /// IPIs.
+    struct MockIpi<const CORE_COUNT: usize> {
+        pending: [AtomicBool; CORE_COUNT],
+    }
+
+    impl<const CORE_COUNT: usize> MockIpi<CORE_COUNT> {
+        /// Creates a new `MockIpi` with no pending IPIs.
+        const fn new() -> Self {
+            Self {
+                pending: [const { AtomicBool::new(false) }; CORE_COUNT],
+            }
+        }
+
+        /// Returns whether an IPI was pending for the core with the given index, and clears it.
+        fn take(&self, core_index: usize) -> bool {
+            self.pending[core_index].swap(false, Ordering::Acquire)
+        }
+    }
+
+    impl<const CORE_COUNT: usize> Ipi for MockIpi<CORE_COUNT> {
+        fn raise(&self, core_index: usize) {
+            self.pending[core_index].store(true, Ordering::Release);
+        }
+    }
+
+    #[test]
+    fn sgi1r() {
+        assert_eq!(sgi1r_value(0x0000_0000, 3), 0x0300_0001);
+        assert_eq!(sgi1r_value(0x8000_0102, 1), 0x0101_0004);
+        assert_eq!(sgi1r_value(0x12_0034_0512, 15), 0x0012_1034_0f05_0004);
+    }
+
+    #[test]
+    fn post_and_handle() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        static CROSS_CALL: CrossCall<MockIpi<2>, 4, 2, FakeCoresImpl> =
+            CrossCall::new(MockIpi::new());
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        FakeCoresImpl::set_core_index(0);
+        CROSS_CALL
+            .post(token, 1, &|_| {
+                COUNT.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+        assert!(!CROSS_CALL.ipi().take(0));
+        assert!(CROSS_CALL.ipi().take(1));
+        CROSS_CALL.handle_ipi(token);
+        assert_eq!(COUNT.load(Ordering::SeqCst), 0);
+
+        thread::spawn(|| {
+            // SAFETY: There are no exceptions in the simulated environment of the tests.
+            let token = unsafe { ExceptionFree::new() };
+            FakeCoresImpl::set_core_index(1);
+            CROSS_CALL.handle_ipi(token);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(COUNT.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn run_on_all_cores_waits() {
+        static CROSS_CALL: CrossCall<MockIpi<3>, 1, 3, FakeCoresImpl> =
+            CrossCall::new(MockIpi::new());
+        static DONE: AtomicBool = AtomicBool::new(false);
+
+        let secondaries: Vec<_> = (1..3)
+            .map(|core_index| {
+                thread::spawn(move || {
+                    // SAFETY: There are no exceptions in the simulated environment of the tests.
+                    let token = unsafe { ExceptionFree::new() };
+                    FakeCoresImpl::set_core_index(core_index);
+                    while !DONE.load(Ordering::Acquire) {
+                        if CROSS_CALL.ipi().take(core_index) {
+                            CROSS_CALL.handle_ipi(token);
+                        }
+                        spin_loop();
+                    }
+                })
+            })
+            .collect();
+
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        FakeCoresImpl::set_core_index(0);
+        let ran_on = [const { AtomicBool::new(false) }; 3];
+        CROSS_CALL.run_on_all_cores(token, |_| {
+            ran_on[FakeCoresImpl::core_index()].store(true, Ordering::SeqCst);
+        });
+        assert!(ran_on.iter().all(|ran| ran.load(Ordering::SeqCst)));
+
+        let value = AtomicUsize::new(0);
+        CROSS_CALL.run_on_core(token, 2, |_| {
+            value.store(FakeCoresImpl::core_index(), Ordering::SeqCst);
+        });
+        assert_eq!(value.load(Ordering::SeqCst), 2);
+
+        DONE.store(true, Ordering::Release);
+        for secondary in secondaries {
+            secondary.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn run_on_all_cores_waits_after_panic() {
+        static CROSS_CALL: CrossCall<MockIpi<3>, 1, 3, FakeCoresImpl> =
+            CrossCall::new(MockIpi::new());
+        static DONE: AtomicBool = AtomicBool::new(false);
+
+        // Only core 1 handles its calls, core 2's inbox stays full.
+        let secondary = thread::spawn(|| {
+            // SAFETY: There are no exceptions in the simulated environment of the tests.
+            let token = unsafe { ExceptionFree::new() };
+            FakeCoresImpl::set_core_index(1);
+            while !DONE.load(Ordering::Acquire) {
+                if CROSS_CALL.ipi().take(1) {
+                    CROSS_CALL.handle_ipi(token);
+                }
+                spin_loop();
+            }
+        });
+
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        FakeCoresImpl::set_core_index(0);
+        CROSS_CALL.post(token, 2, &|_| {}).unwrap();
+        // Handled by core 0 while it waits for space in core 2's inbox.
+        CROSS_CALL
+            .post(token, 0, &|_| panic!("Cross-call failed"))
+            .unwrap();
+
+        let ran_on = [const { AtomicBool::new(false) }; 3];
+        assert!(
+            catch_unwind(AssertUnwindSafe(|| {
+                CROSS_CALL.run_on_all_cores(token, |_| {
+                    ran_on[FakeCoresImpl::core_index()].store(true, Ordering::SeqCst);
+                });
+            }))
+            .is_err()
+        );
+        // The call already sent to core 1 finished before the closure was dropped.
+        assert!(ran_on[1].load(Ordering::SeqCst));
+        assert!(!ran_on[2].load(Ordering::SeqCst));
+
+        DONE.store(true, Ordering::Release);
+        secondary.join().unwrap();
+    }
+}
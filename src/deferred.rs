@@ -0,0 +1,307 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CoreSync, ExceptionFree, ExceptionLock, queue::ArrayQueue};
+use core::cell::RefCell;
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+use {
+    crate::exceptions::exception_free_without_deferred,
+    core::{
+        mem::{forget, transmute},
+        ptr::null_mut,
+        sync::atomic::{AtomicPtr, Ordering},
+    },
+};
+
+/// Function registered with [`set_deferred_work_runner`], or null if none has been registered.
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+static DEFERRED_WORK_RUNNER: AtomicPtr<()> = AtomicPtr::new(null_mut());
+
+/// Sets the function which [`exception_free`](crate::exception_free) will call after it unmasks
+/// exceptions, to run any work deferred on the current core.
+///
+/// This will usually call [`DeferQueue::run_pending`] on the current core's queue, e.g.:
+///
+/// ```no_run
+/// # use percore::{Cores, DeferQueue, PerCore};
+/// # #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+/// # use percore::set_deferred_work_runner;
+/// # struct CoresImpl;
+/// # unsafe impl Cores for CoresImpl {
+/// #     fn core_index() -> usize { 0 }
+/// # }
+/// static DEFERRED: PerCore<[DeferQueue<8>; 2], CoresImpl> =
+///     PerCore::new([const { DeferQueue::new() }; 2]);
+///
+/// # #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+/// set_deferred_work_runner(|| DEFERRED.get().run_pending());
+/// ```
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+pub fn set_deferred_work_runner(runner: fn()) {
+    DEFERRED_WORK_RUNNER.store(runner as *mut (), Ordering::Release);
+}
+
+/// Calls the function registered with [`set_deferred_work_runner`], if any.
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+pub(crate) fn run_deferred_work() {
+    let runner = DEFERRED_WORK_RUNNER.load(Ordering::Acquire);
+    if !runner.is_null() {
+        // SAFETY: The only non-null value ever stored in `DEFERRED_WORK_RUNNER` is a `fn()`.
+        let runner = unsafe { transmute::<*mut (), fn()>(runner) };
+        runner();
+    }
+}
+
+/// A fixed-capacity queue of work deferred until exceptions are unmasked on a CPU core.
+///
+/// This is intended to be stored in a [`PerCore`](crate::PerCore), with one queue for each core.
+/// Code running with exceptions masked, such as within [`exception_free`](crate::exception_free)
+/// or an exception handler, can call [`defer`](Self::defer) to add a function to the current
+/// core's queue. The queued functions are later run in order by [`run_pending`](Self::run_pending),
+/// which `exception_free` calls automatically when it restores an unmasked state if it has been
+/// registered with [`set_deferred_work_runner`].
+pub struct DeferQueue<const CAP: usize> {
+    state: ExceptionLock<RefCell<DeferState<CAP>>>,
+}
+
+struct DeferState<const CAP: usize> {
+    queue: ArrayQueue<fn(), CAP>,
+    /// Whether `run_pending` is currently running on this queue.
+    #[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+    running: bool,
+}
+
+impl<const CAP: usize> DeferQueue<CAP> {
+    /// Creates a new empty queue.
+    pub const fn new() -> Self {
+        Self {
+            state: ExceptionLock::new(RefCell::new(DeferState {
+                queue: ArrayQueue::new(),
+                #[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+                running: false,
+            })),
+        }
+    }
+
+    /// Adds the given function to the queue, to be run after exceptions are next unmasked.
+    ///
+    /// Returns the function back as an error if the queue is full.
+    pub fn defer(&self, token: ExceptionFree, work: fn()) -> Result<(), fn()> {
+        self.state.borrow_mut(token).queue.push(work)
+    }
+
+    /// Returns the number of functions currently waiting in the queue.
+    pub fn len(&self, token: ExceptionFree) -> usize {
+        self.state.borrow_mut(token).queue.len()
+    }
+
+    /// Returns whether the queue is currently empty.
+    pub fn is_empty(&self, token: ExceptionFree) -> bool {
+        self.len(token) == 0
+    }
+
+    /// Removes and returns the oldest function from the queue, without running it.
+    pub fn take(&self, token: ExceptionFree) -> Option<fn()> {
+        self.state.borrow_mut(token).queue.pop()
+    }
+
+    /// Runs all functions in the queue in the order they were added, with exceptions unmasked.
+    ///
+    /// Exceptions are masked only briefly while taking each function from the queue, so exception
+    /// handlers may defer more work while this is running, which will also be run before it
+    /// returns.
+    ///
+    /// If this is called again while it is already running on the same queue (e.g. by a deferred
+    /// function which calls `exception_free`) then the nested call returns immediately, and the
+    /// outer call continues to run the remaining work.
+    #[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+    pub fn run_pending(&self) {
+        let already_running = exception_free_without_deferred(|token| {
+            let mut state = self.state.borrow_mut(token);
+            let already_running = state.running;
+            state.running = true;
+            already_running
+        });
+        if already_running {
+            return;
+        }
+        // If a deferred function panics, the queue must not be left marked as running, or it would
+        // never run again.
+        let guard = RunningGuard { queue: self };
+        while let Some(work) = exception_free_without_deferred(|token| self.next_work(token)) {
+            work();
+        }
+        // `next_work` already marked the queue as no longer running when it found it empty.
+        forget(guard);
+    }
+
+    /// Takes the next function from the queue, or marks the queue as no longer running if it is
+    /// empty.
+    #[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+    fn next_work(&self, token: ExceptionFree) -> Option<fn()> {
+        let mut state = self.state.borrow_mut(token);
+        let work = state.queue.pop();
+        if work.is_none() {
+            state.running = false;
+        }
+        work
+    }
+}
+
+/// Marks a [`DeferQueue`] as no longer running when dropped, if `run_pending` unwinds.
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+struct RunningGuard<'a, const CAP: usize> {
+    queue: &'a DeferQueue<CAP>,
+}
+
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+impl<const CAP: usize> Drop for RunningGuard<'_, CAP> {
+    fn drop(&mut self) {
+        exception_free_without_deferred(|token| {
+            self.queue.state.borrow_mut(token).running = false;
+        });
+    }
+}
+
+impl<const CAP: usize> Default for DeferQueue<CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SAFETY: The queue state is only accessed through an `ExceptionLock`, and `fn()` is `Send`.
+unsafe impl<const CAP: usize> CoreSync for DeferQueue<CAP> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PerCore, tests::FakeCoresImpl};
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    static LOG: AtomicU32 = AtomicU32::new(0);
+
+    fn work_a() {
+        LOG.store(LOG.load(Ordering::SeqCst) * 10 + 1, Ordering::SeqCst);
+    }
+
+    fn work_b() {
+        LOG.store(LOG.load(Ordering::SeqCst) * 10 + 2, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn defer_and_take_in_order() {
+        let queue = DeferQueue::<2>::new();
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        assert!(queue.is_empty(token));
+        assert!(queue.defer(token, work_b).is_ok());
+        assert!(queue.defer(token, work_a).is_ok());
+        assert!(queue.defer(token, work_a).is_err());
+        assert_eq!(queue.len(token), 2);
+
+        while let Some(work) = queue.take(token) {
+            work();
+        }
+        assert_eq!(LOG.load(Ordering::SeqCst), 21);
+        assert!(queue.is_empty(token));
+    }
+
+    #[test]
+    fn run_pending_in_order() {
+        static QUEUES: PerCore<[DeferQueue<4>; 1], FakeCoresImpl> =
+            PerCore::new([const { DeferQueue::new() }; 1]);
+        static RUN_LOG: AtomicU32 = AtomicU32::new(0);
+
+        fn log(digit: u32) {
+            let value = RUN_LOG.load(Ordering::SeqCst) * 10 + digit;
+            RUN_LOG.store(value, Ordering::SeqCst);
+        }
+
+        fn first() {
+            log(1);
+            // Work deferred while running is picked up by the same call.
+            exception_free_without_deferred(|token| QUEUES.get().defer(token, third).unwrap());
+            // A nested call returns immediately, leaving the outer call to run the rest.
+            QUEUES.get().run_pending();
+            assert_eq!(RUN_LOG.load(Ordering::SeqCst), 1);
+        }
+
+        fn second() {
+            log(2);
+        }
+
+        fn third() {
+            log(3);
+        }
+
+        FakeCoresImpl::set_core_index(0);
+        let queue = QUEUES.get();
+        exception_free_without_deferred(|token| {
+            queue.defer(token, first).unwrap();
+            queue.defer(token, second).unwrap();
+        });
+        queue.run_pending();
+        assert_eq!(RUN_LOG.load(Ordering::SeqCst), 123);
+        assert!(exception_free_without_deferred(
+            |token| queue.is_empty(token)
+        ));
+
+        // The queue can be run again once the previous run has finished.
+        exception_free_without_deferred(|token| queue.defer(token, second).unwrap());
+        queue.run_pending();
+        assert_eq!(RUN_LOG.load(Ordering::SeqCst), 1232);
+    }
+
+    #[test]
+    fn run_pending_after_panic() {
+        static QUEUES: PerCore<[DeferQueue<4>; 1], FakeCoresImpl> =
+            PerCore::new([const { DeferQueue::new() }; 1]);
+        static RUN_COUNT: AtomicU32 = AtomicU32::new(0);
+
+        fn panics() {
+            panic!("Deferred work failed");
+        }
+
+        fn work() {
+            RUN_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        FakeCoresImpl::set_core_index(0);
+        let queue = QUEUES.get();
+        exception_free_without_deferred(|token| {
+            queue.defer(token, panics).unwrap();
+            queue.defer(token, work).unwrap();
+        });
+        assert!(catch_unwind(AssertUnwindSafe(|| queue.run_pending())).is_err());
+        assert_eq!(RUN_COUNT.load(Ordering::SeqCst), 0);
+
+        // The queue isn't stuck as running, so the remaining work still runs.
+        queue.run_pending();
+        assert_eq!(RUN_COUNT.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn exception_free_runs_deferred_work_when_unmasked() {
+        static QUEUES: PerCore<[DeferQueue<4>; 1], FakeCoresImpl> =
+            PerCore::new([const { DeferQueue::new() }; 1]);
+        static RUN_COUNT: AtomicU32 = AtomicU32::new(0);
+
+        fn work() {
+            RUN_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        FakeCoresImpl::set_core_index(0);
+        set_deferred_work_runner(|| QUEUES.get().run_pending());
+        crate::exception_free(|token| {
+            QUEUES.get().defer(token, work).unwrap();
+            // Restoring a masked state doesn't run the deferred work.
+            crate::exception_free(|token| QUEUES.get().defer(token, work).unwrap());
+            assert_eq!(RUN_COUNT.load(Ordering::SeqCst), 0);
+            assert_eq!(QUEUES.get().len(token), 2);
+        });
+        assert_eq!(RUN_COUNT.load(Ordering::SeqCst), 2);
+    }
+}
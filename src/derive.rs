@@ -50,10 +50,44 @@
 //!    `percore` section must never be modified (or at least not until all cores have started and
 //!    initialised their copies). `percore_copy_secondary_data` can also be used for this.
 //!
+//! If the `percore` section still contains the initial values, a single core's area can later be
+//! re-initialised with [`percore_init_core`] (or [`aarch64::percore_init_core`] from assembly), e.g.
+//! when the core is brought back online after being powered off. The layout described in
+//! [Pristine initial image](#pristine-initial-image) guarantees this.
+//!
 //! In any case, you must ensure that the alignment of each CPU's percore area is greater than or
 //! equal to to the maximum alignment of any percore variable, and that the memory has exposed
 //! provenance.
 //!
+//! # Pristine initial image
+//!
+//! Rather than having the primary core use the `percore` section as its percore area, you can keep
+//! the initial values in a read-only output section and give every core, including the primary
+//! core, its own writable copy. The initial image then stays intact, so any core can be
+//! (re-)initialised from it at any time. E.g.:
+//!
+//! ```ld
+//! percore_init : ALIGN(CACHE_LINE_SIZE) {
+//!     __start_percore = .;
+//!     *(SORT_BY_ALIGNMENT(percore percore.*))
+//!     . = ALIGN(ALIGNOF(percore_init));
+//!     __stop_percore = .;
+//! } >rodata
+//!
+//! percore_cores (NOLOAD) : ALIGN(ALIGNOF(percore_init)) {
+//!     __start_percore_cores = .;
+//!     . += (__stop_percore - __start_percore) * CORE_COUNT;
+//!     __stop_percore_cores = .;
+//! } >data
+//! ```
+//!
+//! Copy the image into each core's area with [`percore_copy_secondary_data`] or
+//! [`percore_init_core`], and use [`percore_area_offset`] to calculate the offset for each core. The
+//! slot of each core in the `percore_cores` section is its core index, including for core 0. On
+//! AArch64 bare-metal targets the functions in [`aarch64::pristine`] implement the same in assembly
+//! using the `__start_percore_cores` and `__stop_percore_cores` symbols. In this layout no core may
+//! use an offset of 0, as the `percore` section is read-only.
+//!
 //! # Usage
 //!
 //! All cores will have their own instance of `VARIABLE` which is initialized to 1.
@@ -77,9 +111,13 @@
 #[cfg(all(target_arch = "aarch64", target_os = "none"))]
 pub mod aarch64;
 
-use crate::lock::ExceptionLock;
-use core::ptr::with_exposed_provenance;
-pub use percore_derive::percore;
+use crate::CoreSync;
+use core::{
+    mem::size_of,
+    ptr::{with_exposed_provenance, with_exposed_provenance_mut},
+    slice,
+};
+pub use percore_derive::{percore, percore_init, percore_resume, percore_teardown};
 
 #[allow(improper_ctypes)]
 unsafe extern "Rust" {
@@ -121,6 +159,72 @@ unsafe extern "Rust" {
     pub safe static STOP_PERCORE: ();
 }
 
+/// Declares the symbols marking the start and end of the linker section with the given name.
+///
+/// The Mach-O section name is given separately, as it is limited to 16 characters.
+macro_rules! section_bounds {
+    ($start:ident, $stop:ident, $section:literal, $macho_section:literal) => {
+        #[allow(improper_ctypes)]
+        unsafe extern "Rust" {
+            #[doc = concat!("Symbol marking the start of the `", $section, "` section.")]
+            #[cfg_attr(
+                                any(
+                                    target_os = "none",
+                                    target_os = "linux",
+                                    target_os = "android",
+                                    target_os = "fuchsia",
+                                    target_os = "psp",
+                                    target_os = "freebsd",
+                                    target_os = "openbsd",
+                                ),
+                                link_name = concat!("__start_", $section)
+                            )]
+            #[cfg_attr(
+                                any(target_os = "macos", target_os = "ios", target_os = "tvos"),
+                                link_name = concat!("\x01section$start$__DATA$", $macho_section)
+                            )]
+            safe static $start: ();
+            #[doc = concat!("Symbol marking the end of the `", $section, "` section.")]
+            #[cfg_attr(
+                                any(
+                                    target_os = "none",
+                                    target_os = "linux",
+                                    target_os = "android",
+                                    target_os = "fuchsia",
+                                    target_os = "psp",
+                                    target_os = "freebsd",
+                                    target_os = "openbsd",
+                                ),
+                                link_name = concat!("__stop_", $section)
+                            )]
+            #[cfg_attr(
+                                any(target_os = "macos", target_os = "ios", target_os = "tvos"),
+                                link_name = concat!("\x01section$end$__DATA$", $macho_section)
+                            )]
+            safe static $stop: ();
+        }
+    };
+}
+
+section_bounds!(
+    START_PERCORE_ONLINE,
+    STOP_PERCORE_ONLINE,
+    "percore_online",
+    "__percore_on"
+);
+section_bounds!(
+    START_PERCORE_OFFLINE,
+    STOP_PERCORE_OFFLINE,
+    "percore_offline",
+    "__percore_off"
+);
+section_bounds!(
+    START_PERCORE_RESUME,
+    STOP_PERCORE_RESUME,
+    "percore_resume",
+    "__percore_resum"
+);
+
 /// Returns the size in bytes of a single core's `percore` section.
 pub fn percore_size() -> usize {
     &raw const STOP_PERCORE as usize - &raw const START_PERCORE as usize
@@ -175,6 +279,256 @@ pub unsafe fn percore_copy_secondary_data(secondary_percore_area: *mut [u8]) {
     secondary_percore_area.expose_provenance();
 }
 
+/// Re-initialises a single core's copy of the percore variables, by copying the `percore` section
+/// into the given slot in `percore_area`, e.g. when the core is brought back online after being
+/// powered off.
+///
+/// `percore_area` is the array of per-core areas, as would be passed to
+/// [`percore_copy_secondary_data`], and `slot` is the index of the slot within it. This is not
+/// necessarily the index of the core which owns the slot: if the primary core 0 uses the `percore`
+/// section itself and `percore_area` only holds the secondary cores' areas, then the slot of core
+/// `n` is `n - 1`. `aarch64::percore_init_core` takes the core index instead.
+///
+/// The `percore` section must still contain the initial values of all percore variables, e.g.
+/// because no core uses it as its percore area, as in the
+/// [pristine layout](self#pristine-initial-image).
+///
+/// Panics if the slot isn't within `percore_area`.
+///
+/// This also exposes the provenance of the slot, as `LinkedPerCore::get` will later construct a
+/// pointer to it with exposed provenance.
+///
+/// # Safety
+///
+/// No references to any of the percore variables in the slot may exist, and the core it belongs to
+/// must not access them until this has completed.
+///
+/// `percore_area` must be valid for writes, and must not overlap with the `percore` section.
+///
+/// If this is called on a different core to the one which owns the slot, you must ensure that it
+/// happens-before that core accesses any percore variables (according to Rust's memory model).
+/// This could for example be achieved by writing to an AtomicBool with release semantics and having
+/// the core wait until it sees the written value with acquire semantics, or by calling this before
+/// powering the core on, with appropriate cache maintenance.
+pub unsafe fn percore_init_core(percore_area: *mut [u8], slot: usize) {
+    let percore_size = percore_size();
+    let start = slot.checked_mul(percore_size).unwrap();
+    assert!(start.checked_add(percore_size).unwrap() <= percore_area.len());
+
+    let dest = (percore_area as *mut u8).wrapping_byte_add(start);
+    // SAFETY: The caller promises that `percore_area` is valid to write and doesn't overlap with
+    // the `percore` section, and that nothing is accessing the slot. We checked that the slot is
+    // within `percore_area`.
+    unsafe {
+        (&raw const START_PERCORE)
+            .cast::<u8>()
+            .copy_to_nonoverlapping(dest, percore_size);
+    }
+
+    // Expose the provenance of the slot, because `LinkedPerCore::get` will construct a pointer to it
+    // with exposed provenance.
+    dest.expose_provenance();
+}
+
+/// Returns the offset of the given slot in `percore_area` from the `percore` section, as should be
+/// returned by [`PercoreLocalOffset::percore_local_offset`] on the core which owns the slot.
+///
+/// `percore_area` is the array of per-core areas, as would be passed to
+/// [`percore_copy_secondary_data`], and `slot` is the index of the slot within it. As for
+/// [`percore_init_core`], this is the index of the core in the
+/// [pristine layout](self#pristine-initial-image), but the index of the core minus one if
+/// `percore_area` only holds the secondary cores' areas.
+///
+/// Panics if the slot isn't within `percore_area`.
+pub fn percore_area_offset(percore_area: *const [u8], slot: usize) -> isize {
+    let percore_size = percore_size();
+    let start = slot.checked_mul(percore_size).unwrap();
+    assert!(start.checked_add(percore_size).unwrap() <= percore_area.len());
+
+    // Like the offset applied by the generated code, this wraps around the address space, so it is
+    // valid even if the addresses don't fit in an `isize`.
+    (percore_area as *const u8)
+        .addr()
+        .wrapping_add(start)
+        .wrapping_sub((&raw const START_PERCORE).addr())
+        .cast_signed()
+}
+
+/// Returns a pointer to the start of the local core's percore area.
+fn local_percore_area() -> *mut u8 {
+    // As in `LinkedPerCore::ptr_at_offset`, the local area may not be part of the same allocation
+    // as the `percore` section, so we must use exposed provenance.
+    with_exposed_provenance_mut(
+        (&raw const START_PERCORE)
+            .expose_provenance()
+            .wrapping_add_signed(percore_local_offset()),
+    )
+}
+
+/// Copies the local core's percore area into the given buffer, so that it can be restored with
+/// [`percore_restore`] after the core's percore memory has been lost, e.g. by `CPU_SUSPEND` to a
+/// power-down state.
+///
+/// The buffer may be in memory which is retained while the core is suspended, or in memory which
+/// will be saved by some other means.
+///
+/// Panics if `saved` is smaller than [`percore_size`].
+///
+/// # Safety
+///
+/// This must be called on the core being suspended, with exceptions masked and no outstanding
+/// references to any of its percore variables, and no percore variable may be modified between
+/// this and the core being suspended. The local percore offset must be valid.
+///
+/// `saved` must be valid for writes, and must not overlap with any core's percore area.
+pub unsafe fn percore_save(saved: *mut [u8]) {
+    let percore_size = percore_size();
+    assert!(saved.len() >= percore_size);
+    // SAFETY: Our caller promises that the local percore area is valid, that nothing is modifying
+    // it, and that `saved` is valid for writes and doesn't overlap it. We checked that `saved` is
+    // large enough.
+    unsafe {
+        local_percore_area().copy_to_nonoverlapping(saved.cast::<u8>(), percore_size);
+    }
+}
+
+/// Copies a buffer previously written by [`percore_save`] back into the local core's percore area,
+/// e.g. on warm boot after `CPU_SUSPEND`.
+///
+/// This only restores the contents of the percore variables. Call [`core_resume`] afterwards to run
+/// the hooks registered with [`percore_resume`], for values such as hardware handles which must be
+/// re-derived rather than copied.
+///
+/// Panics if `saved` is smaller than [`percore_size`].
+///
+/// # Safety
+///
+/// This must be called on the core which saved the buffer, before any percore variable is accessed
+/// on it after resuming. The local percore offset must be valid, and must be the same as when the
+/// buffer was saved.
+///
+/// `saved` must be valid for reads, must contain the data written by `percore_save` on the same
+/// core with the same image, and must not overlap with any core's percore area.
+pub unsafe fn percore_restore(saved: *const [u8]) {
+    let percore_size = percore_size();
+    assert!(saved.len() >= percore_size);
+    // SAFETY: Our caller promises that the local percore area is valid and that nothing is
+    // accessing it yet, and that `saved` is valid for reads, doesn't overlap it, and contains a
+    // valid copy of the percore area. We checked that `saved` is large enough.
+    unsafe {
+        saved
+            .cast::<u8>()
+            .copy_to_nonoverlapping(local_percore_area(), percore_size);
+    }
+}
+
+/// A function registered with [`percore_init`], [`percore_teardown`] or [`percore_resume`], to be
+/// called with the index of a core when it comes online, goes offline or resumes from suspend.
+///
+/// This should generally not be constructed directly, but through the [`percore_init`],
+/// [`percore_teardown`] or [`percore_resume`] macro.
+#[repr(transparent)]
+pub struct LifecycleHook(fn(usize));
+
+impl LifecycleHook {
+    /// Creates a new hook which calls the given function.
+    pub const fn new(hook: fn(usize)) -> Self {
+        Self(hook)
+    }
+}
+
+/// Returns the hooks in the linker section between the given symbols.
+///
+/// # Safety
+///
+/// `start` and `stop` must mark the boundaries of a linker section containing only
+/// `LifecycleHook`s.
+unsafe fn hooks(
+    start: *const LifecycleHook,
+    stop: *const LifecycleHook,
+) -> &'static [LifecycleHook] {
+    let len = (stop.addr() - start.addr()) / size_of::<LifecycleHook>();
+    // SAFETY: Our caller promises that the section between `start` and `stop` contains only
+    // `LifecycleHook`s, which are all statics and so are valid for the `'static` lifetime.
+    unsafe { slice::from_raw_parts(start, len) }
+}
+
+/// Runs all the hooks registered with [`percore_init`], in link order, for the core with the given
+/// index.
+///
+/// This should be called on the core which is coming online, after its percore area has been
+/// initialised and before it accesses any per-core state, both when it first boots and each time it
+/// is brought back after being powered off (e.g. with PSCI `CPU_OFF`). The hooks can then
+/// re-initialise both `PerCore` values and `#[percore]` variables for the core.
+///
+/// The `percore_online` section must be included in your linker script, with
+/// `__start_percore_online` and `__stop_percore_online` symbols to mark its boundaries, e.g.:
+///
+/// ```ld
+/// percore_online : ALIGN(8) {
+///     __start_percore_online = .;
+///     KEEP(*(percore_online))
+///     __stop_percore_online = .;
+/// } >image
+/// ```
+pub fn core_online(core_index: usize) {
+    // SAFETY: The `percore_online` section only contains the hooks placed there by `percore_init`.
+    for hook in unsafe {
+        hooks(
+            (&raw const START_PERCORE_ONLINE).cast(),
+            (&raw const STOP_PERCORE_ONLINE).cast(),
+        )
+    } {
+        (hook.0)(core_index);
+    }
+}
+
+/// Runs all the hooks registered with [`percore_teardown`], in reverse link order, for the core with
+/// the given index.
+///
+/// This should be called on the core which is going offline, before it is powered off (e.g. with
+/// PSCI `CPU_OFF`).
+///
+/// The `percore_offline` section must be included in your linker script in the same way as the
+/// `percore_online` section described for [`core_online`], with `__start_percore_offline` and
+/// `__stop_percore_offline` symbols.
+pub fn core_offline(core_index: usize) {
+    // SAFETY: The `percore_offline` section only contains the hooks placed there by
+    // `percore_teardown`.
+    let hooks = unsafe {
+        hooks(
+            (&raw const START_PERCORE_OFFLINE).cast(),
+            (&raw const STOP_PERCORE_OFFLINE).cast(),
+        )
+    };
+    for hook in hooks.iter().rev() {
+        (hook.0)(core_index);
+    }
+}
+
+/// Runs all the hooks registered with [`percore_resume`], in link order, for the core with the
+/// given index.
+///
+/// This should be called on the core which is resuming from suspend, after its percore area has
+/// been restored with [`percore_restore`]. The hooks can then re-derive any values which aren't
+/// valid after being copied, such as hardware handles.
+///
+/// The `percore_resume` section must be included in your linker script in the same way as the
+/// `percore_online` section described for [`core_online`], with `__start_percore_resume` and
+/// `__stop_percore_resume` symbols.
+pub fn core_resume(core_index: usize) {
+    // SAFETY: The `percore_resume` section only contains the hooks placed there by
+    // `percore_resume`.
+    for hook in unsafe {
+        hooks(
+            (&raw const START_PERCORE_RESUME).cast(),
+            (&raw const STOP_PERCORE_RESUME).cast(),
+        )
+    } {
+        (hook.0)(core_index);
+    }
+}
+
 /// Provides the offset of the local core's percore area.
 ///
 /// The consuming project must implement this trait for a type and mark that type with the
@@ -225,6 +579,45 @@ impl<T> LinkedPerCore<T> {
     /// Returns a shared reference to the value for the current CPU core.
     #[inline(always)]
     pub fn get(&self) -> &T {
+        let percore_ptr = self.ptr_at_offset(percore_local_offset());
+
+        // SAFETY:
+        // * The percore region must be aligned to the maximum alignment of any percore variable,
+        //   and `&self.0` must be aligned as it comes from a reference, so adding the offset to it
+        //   must still be properly aligned. (In debug builds we also double-check with the
+        //   debug_assert in `ptr_at_offset`.)
+        // * The pointer is non-null because the offset is guaranteed to produce a valid address.
+        // * The PercoreLocalOffset implementation promises that the calculated pointer points into
+        // * the percore memory area which is initialized and it is dereferenceable for the T type.
+        // * Aliasing is prevented by each core having its own instance of the variable and by
+        //   requiring `CoreSync` for `Sync` implementation.
+        unsafe { &*percore_ptr }
+    }
+
+    /// Returns a shared reference to the copy of the value in the percore area at the given offset,
+    /// which may belong to a different CPU core.
+    ///
+    /// This is only available for values which can be safely accessed from any core.
+    ///
+    /// # Safety
+    ///
+    /// The offset must be the offset of a valid and initialised percore area from the `percore`
+    /// section, as would be returned by [`PercoreLocalOffset`] on some core.
+    pub unsafe fn get_at_offset(&self, offset: isize) -> &T
+    where
+        T: Sync,
+    {
+        let percore_ptr = self.ptr_at_offset(offset);
+
+        // SAFETY: As for `get`, our caller promises that the offset points to an initialised percore
+        // area, so the pointer is valid and aligned. `T` is `Sync` so it may be shared with other
+        // cores.
+        unsafe { &*percore_ptr }
+    }
+
+    /// Returns a pointer to the copy of the value in the percore area at the given offset.
+    #[inline(always)]
+    fn ptr_at_offset(&self, offset: isize) -> *const T {
         // We need to construct a new pointer with exposed provenance rather than just using
         // `byte_offset` on the pointer to `self.0` because the per-core copy is not part of the
         // same allocation.
@@ -232,31 +625,21 @@ impl<T> LinkedPerCore<T> {
             ((&raw const self.0)
                 .expose_provenance()
                 .cast_signed()
-                .wrapping_add(percore_local_offset()))
+                .wrapping_add(offset))
             .cast_unsigned(),
         );
 
         debug_assert!(!percore_ptr.is_null());
         debug_assert!(percore_ptr.is_aligned());
 
-        // SAFETY:
-        // * The percore region must be aligned to the maximum alignment of any percore variable,
-        //   and `&self.0` must be aligned as it comes from a reference, so adding the offset to it
-        //   must still be properly aligned. (In debug builds we also double-check with the
-        //   debug_assert above.)
-        // * The pointer is non-null because the offset is guaranteed to produce a valid address.
-        // * The PercoreLocalOffset implementation promises that the calculated pointer points into
-        // * the percore memory area which is initialized and it is dereferenceable for the T type.
-        // * Aliasing is prevented by each core having its own instance of the variable and by
-        //   requiring `ExceptionLock` for `Sync` implementation.
-        unsafe { &*percore_ptr }
+        percore_ptr
     }
 }
 
 // SAFETY: `LinkedPerCore` is safe between different cores, because each core has its own
-// core-local instance of the variable. `ExceptionLock` also prevents concurrent access from runtime
-// and exception context.
-unsafe impl<T: Send> Sync for LinkedPerCore<ExceptionLock<T>> {}
+// core-local instance of the variable. `CoreSync` also prevents concurrent access from runtime and
+// exception context.
+unsafe impl<T: CoreSync> Sync for LinkedPerCore<T> {}
 
 /// Marks the type that implements [`PercoreLocalOffset`].
 ///
@@ -291,9 +674,14 @@ macro_rules! percore_local_offset {
 mod tests {
     use super::*;
     use crate as percore;
-    use crate::ExceptionFree;
-    use core::{cell::RefCell, num::NonZero, ptr::NonNull};
-    use std::{thread, thread_local};
+    use crate::{ExceptionFree, ExceptionLock};
+    use core::{
+        alloc::Layout,
+        cell::RefCell,
+        num::NonZero,
+        ptr::{NonNull, slice_from_raw_parts, slice_from_raw_parts_mut, without_provenance},
+    };
+    use std::{alloc::alloc_zeroed, thread, thread_local};
 
     // We simulate cores with threads for unit tests, so we use a thread-local for the percore
     // region of each thread.
@@ -310,7 +698,11 @@ mod tests {
         fn percore_local_offset() -> isize {
             PERCORE_REGION.with_borrow_mut(|region| {
                 let region = region.get_or_insert_with(|| {
-                    let new_region = Box::into_raw(vec![0; percore_size()].into_boxed_slice());
+                    // Align the region to a cache line, as some percore variables require it.
+                    let layout = Layout::from_size_align(percore_size(), 64).unwrap();
+                    // SAFETY: The layout has a non-zero size, as the tests have percore variables.
+                    let new_region =
+                        slice_from_raw_parts_mut(unsafe { alloc_zeroed(layout) }, percore_size());
                     // SAFETY: new_region is valid for writes because we just allocated it, and no
                     // percore variables have been accessed on this core yet because this is the
                     // first time percore_local_offset has been called.
@@ -375,4 +767,199 @@ mod tests {
 
         assert_eq!(*VALUE.get().borrow_mut(token), 1);
     }
+
+    #[test]
+    fn lifecycle_hooks() {
+        use crate::{Cores, CounterCell, PerCore, tests::FakeCoresImpl};
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        #[percore]
+        static EVENTS: CounterCell<u32> = CounterCell::new();
+        static ONLINE: PerCore<[AtomicBool; 2], FakeCoresImpl> =
+            PerCore::new([const { AtomicBool::new(false) }; 2]);
+
+        #[percore_init]
+        fn init(core_index: usize) {
+            EVENTS.get().reset();
+            assert_eq!(FakeCoresImpl::core_index(), core_index);
+            ONLINE.get().store(true, Ordering::Relaxed);
+        }
+
+        #[percore_teardown]
+        fn teardown(_core_index: usize) {
+            ONLINE.get().store(false, Ordering::Relaxed);
+        }
+
+        thread::spawn(|| {
+            FakeCoresImpl::set_core_index(1);
+            EVENTS.get().add(3);
+            core_offline(1);
+            assert!(!ONLINE.get().load(Ordering::Relaxed));
+            core_online(1);
+            assert!(ONLINE.get().load(Ordering::Relaxed));
+            assert_eq!(EVENTS.get().get(), 0);
+        })
+        .join()
+        .unwrap();
+        assert!(!ONLINE.get_for(0).unwrap().load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn save_restore() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        #[percore]
+        static VALUE: ExceptionLock<RefCell<u64>> = ExceptionLock::new(RefCell::new(1));
+        #[percore]
+        static HANDLE: AtomicU32 = AtomicU32::new(0);
+
+        #[percore_resume]
+        fn reopen_handle(core_index: usize) {
+            HANDLE
+                .get()
+                .store(core_index as u32 + 100, Ordering::Relaxed);
+        }
+
+        thread::spawn(|| {
+            // SAFETY: There are no exceptions in the simulated environment of the tests.
+            let token = unsafe { ExceptionFree::new() };
+            *VALUE.get().borrow_mut(token) = 42;
+            HANDLE.get().store(7, Ordering::Relaxed);
+
+            let mut saved = vec![0; percore_size()];
+            // SAFETY: There are no references to percore variables, and the buffer is a separate
+            // allocation.
+            unsafe {
+                percore_save(&raw mut *saved);
+            }
+
+            // Simulate losing the percore area while suspended.
+            *VALUE.get().borrow_mut(token) = 0;
+            HANDLE.get().store(0, Ordering::Relaxed);
+
+            // SAFETY: The buffer was saved on this core, and there are no references to percore
+            // variables.
+            unsafe {
+                percore_restore(&raw const *saved);
+            }
+            assert_eq!(*VALUE.get().borrow_mut(token), 42);
+            assert_eq!(HANDLE.get().load(Ordering::Relaxed), 7);
+
+            core_resume(3);
+            assert_eq!(HANDLE.get().load(Ordering::Relaxed), 103);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn init_core() {
+        use crate::CounterCell;
+
+        #[percore]
+        static _VALUE: CounterCell<u64> = CounterCell::new();
+
+        let percore_size = percore_size();
+        let mut area = vec![0u8; percore_size * 3];
+        let area = &raw mut *area;
+        // SAFETY: The area is a separate allocation, and nothing is using it yet.
+        unsafe {
+            percore_copy_secondary_data(area);
+        }
+        // SAFETY: `area` is still valid, and no other references to it exist.
+        let slots = unsafe { &mut *area };
+        slots[percore_size..].fill(0xff);
+
+        // SAFETY: The area is a separate allocation, and nothing is using the slot.
+        unsafe {
+            percore_init_core(area, 1);
+        }
+        // SAFETY: `area` is still valid, and no other references to it exist.
+        let slots = unsafe { &*area };
+        assert_eq!(slots[percore_size..percore_size * 2], slots[..percore_size]);
+        assert!(slots[percore_size * 2..].iter().all(|&byte| byte == 0xff));
+
+        // `aarch64::percore_init_core` expects the secondary area to immediately follow the `percore`
+        // section, so the slot of core `n` is `n - 1`, at the offset calculated by
+        // `aarch64::percore_calculate_local_offset(n)`.
+        let secondary_area = slice_from_raw_parts(
+            (&raw const START_PERCORE)
+                .cast::<u8>()
+                .wrapping_add(percore_size),
+            percore_size * 3,
+        );
+        for core_index in 1..=3 {
+            assert_eq!(
+                percore_area_offset(secondary_area, core_index - 1),
+                (percore_size * core_index) as isize
+            );
+        }
+    }
+
+    #[test]
+    fn area_offset_upper_half() {
+        // The area is never accessed, so it doesn't matter that nothing is mapped there.
+        let area_start = 1 << (usize::BITS - 1);
+        let area = slice_from_raw_parts(without_provenance::<u8>(area_start), percore_size() * 2);
+
+        let offset = percore_area_offset(area, 1);
+        assert_eq!(
+            (&raw const START_PERCORE)
+                .addr()
+                .wrapping_add_signed(offset),
+            area_start + percore_size()
+        );
+    }
+
+    #[test]
+    fn area_offset() {
+        use crate::CounterCell;
+
+        #[percore]
+        static VALUE: CounterCell<u64> = CounterCell::new();
+
+        let layout = Layout::from_size_align(percore_size() * 2, 64).unwrap();
+        // SAFETY: The layout has a non-zero size, as the tests have percore variables.
+        let area = slice_from_raw_parts_mut(unsafe { alloc_zeroed(layout) }, layout.size());
+        // SAFETY: The area was just allocated, and nothing is using it yet.
+        unsafe {
+            percore_copy_secondary_data(area);
+        }
+
+        let offsets = [percore_area_offset(area, 0), percore_area_offset(area, 1)];
+        assert_eq!(offsets[1] - offsets[0], percore_size() as isize);
+        // SAFETY: Both offsets point to slots in the area, initialised with a copy of the percore
+        // section, which nothing else is accessing.
+        unsafe {
+            VALUE.get_at_offset(offsets[0]).add(3);
+            VALUE.get_at_offset(offsets[1]).add(4);
+            assert_eq!(VALUE.get_at_offset(offsets[0]).get(), 3);
+            assert_eq!(VALUE.get_at_offset(offsets[1]).get(), 4);
+        }
+    }
+
+    #[test]
+    fn counter_sum() {
+        use crate::CounterCell;
+
+        #[percore]
+        static EVENTS: CounterCell<u32> = CounterCell::new();
+
+        EVENTS.get().add(1);
+        let mut offsets = vec![percore_local_offset()];
+        for _ in 0..2 {
+            offsets.push(
+                thread::spawn(|| {
+                    EVENTS.get().add(5);
+                    percore_local_offset()
+                })
+                .join()
+                .unwrap(),
+            );
+        }
+
+        // SAFETY: The offsets were all returned by `percore_local_offset`, and the regions are
+        // never freed.
+        assert_eq!(unsafe { EVENTS.sum(offsets) }, 11);
+    }
 }
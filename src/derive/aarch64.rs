@@ -20,6 +20,8 @@
 //! Note that the `percore_secondary` section is only used for secondary cores; the `percore`
 //! section itself is used for the primary core's copy of the variables in this case.
 
+pub mod pristine;
+
 use super::{START_PERCORE, STOP_PERCORE};
 use core::arch::naked_asm;
 
@@ -128,3 +130,75 @@ pub extern "C" fn percore_calculate_local_offset(core_index: usize) -> isize {
         STOP_PERCORE = sym STOP_PERCORE,
     )
 }
+
+/// Re-initialises the percore area of a single secondary core by copying the `percore` section into
+/// it, e.g. when the core is brought back online after being powered off. The function is safe to
+/// be called from assembly without a stack present. It clobbers registers X1-X7.
+///
+/// The core's area is located at `__start_percore + (__stop_percore - __start_percore) *
+/// core_index`, as calculated by [`percore_calculate_local_offset`]. If `core_index` is 0 or the area
+/// isn't within the `percore_secondary` section then nothing is copied, as the primary core uses the
+/// `percore` section itself. The copy is done in 16 byte chunks, so the sections must be aligned to
+/// at least a 16 byte boundary. The function is suitable for tiny and small memory models.
+///
+/// Note that this takes the index of the core, whereas
+/// [`derive::percore_init_core`](super::percore_init_core) takes the index of its slot in the
+/// `percore_secondary` section, which is `core_index - 1`.
+///
+/// # Safety
+///
+/// The `percore` section must still contain the initial values of all percore variables, i.e. the
+/// primary core must not have modified any of its percore variables. Nothing may be accessing the
+/// percore variables of the core with the given index.
+///
+/// If this is called on a different core to the one being initialised, you must ensure that it
+/// happens-before that core accesses any percore variables (according to Rust's memory model).
+/// This could be achieved by calling it before powering the core on, with appropriate cache
+/// maintenance to ensure that the copy is visible to the core before its caches are enabled.
+#[unsafe(naked)]
+pub unsafe extern "C" fn percore_init_core(core_index: usize) {
+    naked_asm!(
+        "bti	c
+        adrp	x1, {START_PERCORE}
+        add	x1, x1, :lo12:{START_PERCORE}
+        adrp	x2, {STOP_PERCORE}
+        add	x2, x2, :lo12:{STOP_PERCORE}
+        adrp	x7, {STOP_PERCORE_SECONDARY}
+        add	x7, x7, :lo12:{STOP_PERCORE_SECONDARY}
+
+        /* Check whether this is the primary core. */
+        cbz	x0, 2f
+
+        /* Check whether the percore section is empty. */
+        subs	x3, x2, x1
+        b.eq	2f
+
+        /* Calculate the destination, and check that it is within percore_secondary. */
+        mul	x4, x0, x3
+        add	x4, x1, x4
+        add	x5, x4, x3
+        cmp	x5, x7
+        b.hi	2f
+
+        /*
+         * Data loop
+         * X1: src
+         * X2: src_end
+         * X4: dst
+         * X5, X6: data temp
+         */
+    1:
+        ldp	x5, x6, [x1], #16
+        stp	x5, x6, [x4], #16
+
+        /* src == src_end */
+        cmp	x1, x2
+        b.ne	1b
+
+    2:
+        ret",
+        START_PERCORE = sym START_PERCORE,
+        STOP_PERCORE = sym STOP_PERCORE,
+        STOP_PERCORE_SECONDARY = sym STOP_PERCORE_SECONDARY,
+    )
+}
@@ -0,0 +1,188 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Assembly implementations of percore initialisation helper functions for the pristine layout,
+//! where the `percore` section holds a read-only initial image and every core, including the
+//! primary core, has its own writable copy in the `percore_cores` section.
+//!
+//! These assume that your linker script places the `percore` input sections in a read-only
+//! `percore_init` output section, with `__start_percore` and `__stop_percore` symbols marking its
+//! start and end, and has a `percore_cores` section with `__start_percore_cores` and
+//! `__stop_percore_cores` symbols. See [the module documentation](crate::derive#pristine-initial-image)
+//! for an example.
+
+use super::super::{START_PERCORE, STOP_PERCORE};
+use core::arch::naked_asm;
+
+#[allow(improper_ctypes)]
+unsafe extern "Rust" {
+    /// Symbol marking the start of the `percore_cores` section.
+    #[link_name = "__start_percore_cores"]
+    pub safe static START_PERCORE_CORES: ();
+    /// Symbol marking the end of the `percore_cores` section.
+    #[link_name = "__stop_percore_cores"]
+    pub safe static STOP_PERCORE_CORES: ();
+}
+
+/// Copies the initial image in the `percore` section into every core's percore area in the
+/// `percore_cores` section. The function is safe to be called from assembly without a stack
+/// present. It clobbers registers X0-X6.
+///
+/// The copy is done in 16 byte chunks, so the sections must be aligned to at least a 16 byte
+/// boundary. The function is suitable for tiny and small memory models.
+///
+/// # Safety
+///
+/// This must only be called before any core accesses any percore variable.
+///
+/// You must ensure that this initialisation happens-before any percore variables are accessed
+/// (according to Rust's memory model). This could be achieved by calling it from assembly before
+/// caches are enabled or any Rust code runs.
+#[unsafe(naked)]
+pub unsafe extern "C" fn percore_copy_data() {
+    naked_asm!(
+        "bti	c
+        adrp	x0, {START_PERCORE}
+        add	x0, x0, :lo12:{START_PERCORE}
+        adrp	x1, {STOP_PERCORE}
+        add	x1, x1, :lo12:{STOP_PERCORE}
+        adrp	x2, {START_PERCORE_CORES}
+        add	x2, x2, :lo12:{START_PERCORE_CORES}
+        adrp	x3, {STOP_PERCORE_CORES}
+        add	x3, x3, :lo12:{STOP_PERCORE_CORES}
+
+        /* Check whether the percore section is empty. */
+        cmp	x0, x1
+        b.eq	3f
+
+        /* Check whether the percore_cores section is empty. */
+        cmp	x2, x3
+        b.eq	3f
+
+        /* Save source start pointer */
+        mov	x4, x0
+
+        /*
+         * Per-core loop
+         * X0: src
+         * X1: src_end
+         * X2: dst
+         * X3: dst_end (end of the percore area of the last core)
+         * X5, X6: data temp
+         */
+
+    1:
+        mov	x0, x4
+
+        /* Data loop */
+    2:
+        ldp	x5, x6, [x0], #16
+        stp	x5, x6, [x2], #16
+
+        /* src == src_end */
+        cmp	x0, x1
+        b.ne	2b
+
+        /* dst == dst_end */
+        cmp	x2, x3
+        b.ne	1b
+
+    3:
+        ret",
+        START_PERCORE = sym START_PERCORE,
+        STOP_PERCORE = sym STOP_PERCORE,
+        START_PERCORE_CORES = sym START_PERCORE_CORES,
+        STOP_PERCORE_CORES = sym STOP_PERCORE_CORES,
+    )
+}
+
+/// Calculates the offset of the core's percore area from the `percore` section using the
+/// following formula: `__start_percore_cores - __start_percore + (__stop_percore -
+/// __start_percore) * core_index`. The intended use of this function is to use its output to set
+/// the offset register of the core. The function is safe to be called from assembly without a stack
+/// present. It clobbers registers X0-X3 and returns the offset in X0. The function is suitable for
+/// tiny and small memory models.
+#[unsafe(naked)]
+pub extern "C" fn percore_calculate_local_offset(core_index: usize) -> isize {
+    naked_asm!(
+        "bti	c
+        adrp	x1, {START_PERCORE}
+        add	x1, x1, :lo12:{START_PERCORE}
+        adrp	x2, {STOP_PERCORE}
+        add	x2, x2, :lo12:{STOP_PERCORE}
+        adrp	x3, {START_PERCORE_CORES}
+        add	x3, x3, :lo12:{START_PERCORE_CORES}
+        sub	x2, x2, x1
+        sub	x3, x3, x1
+        madd	x0, x0, x2, x3
+        ret",
+        START_PERCORE = sym START_PERCORE,
+        STOP_PERCORE = sym STOP_PERCORE,
+        START_PERCORE_CORES = sym START_PERCORE_CORES,
+    )
+}
+
+/// Re-initialises the percore area of a single core in the `percore_cores` section by copying the
+/// initial image in the `percore` section into it, e.g. when the core is brought back online after
+/// being powered off. The function is safe to be called from assembly without a stack present. It
+/// clobbers registers X1-X7.
+///
+/// If the core's area isn't within the `percore_cores` section then nothing is copied. The copy is
+/// done in 16 byte chunks, so the sections must be aligned to at least a 16 byte boundary. The
+/// function is suitable for tiny and small memory models.
+///
+/// # Safety
+///
+/// Nothing may be accessing the percore variables of the core with the given index.
+///
+/// If this is called on a different core to the one being initialised, you must ensure that it
+/// happens-before that core accesses any percore variables (according to Rust's memory model).
+/// This could be achieved by calling it before powering the core on, with appropriate cache
+/// maintenance to ensure that the copy is visible to the core before its caches are enabled.
+#[unsafe(naked)]
+pub unsafe extern "C" fn percore_init_core(core_index: usize) {
+    naked_asm!(
+        "bti	c
+        adrp	x1, {START_PERCORE}
+        add	x1, x1, :lo12:{START_PERCORE}
+        adrp	x2, {STOP_PERCORE}
+        add	x2, x2, :lo12:{STOP_PERCORE}
+        adrp	x4, {START_PERCORE_CORES}
+        add	x4, x4, :lo12:{START_PERCORE_CORES}
+        adrp	x7, {STOP_PERCORE_CORES}
+        add	x7, x7, :lo12:{STOP_PERCORE_CORES}
+
+        /* Check whether the percore section is empty. */
+        subs	x3, x2, x1
+        b.eq	2f
+
+        /* Calculate the destination, and check that it is within percore_cores. */
+        madd	x4, x0, x3, x4
+        add	x5, x4, x3
+        cmp	x5, x7
+        b.hi	2f
+
+        /*
+         * Data loop
+         * X1: src
+         * X2: src_end
+         * X4: dst
+         * X5, X6: data temp
+         */
+    1:
+        ldp	x5, x6, [x1], #16
+        stp	x5, x6, [x4], #16
+
+        /* src == src_end */
+        cmp	x1, x2
+        b.ne	1b
+
+    2:
+        ret",
+        START_PERCORE = sym START_PERCORE,
+        STOP_PERCORE = sym STOP_PERCORE,
+        START_PERCORE_CORES = sym START_PERCORE_CORES,
+        STOP_PERCORE_CORES = sym STOP_PERCORE_CORES,
+    )
+}
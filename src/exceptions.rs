@@ -12,6 +12,13 @@ mod aarch32;
 #[cfg(target_arch = "arm")]
 use aarch32::ExceptionMask;
 
+#[cfg(all(test, not(any(target_arch = "aarch64", target_arch = "arm"))))]
+mod fake;
+#[cfg(all(test, not(any(target_arch = "aarch64", target_arch = "arm"))))]
+use fake::ExceptionMask;
+
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+use crate::deferred::run_deferred_work;
 use core::marker::PhantomData;
 
 /// Scope guard for exception-free sections.
@@ -21,13 +28,13 @@ use core::marker::PhantomData;
 ///
 /// We don't expose this in the crate API because if scope guards are dropped in the wrong order
 /// then the mask state won't be properly restored.
-#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
 struct ExceptionGuard {
     /// Previous exception mask state.
     prev: ExceptionMask,
 }
 
-#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
 impl ExceptionGuard {
     /// Masks exceptions and return a scope guard which will unmask them when it is dropped.
     ///
@@ -47,7 +54,7 @@ impl ExceptionGuard {
     }
 }
 
-#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
 impl Drop for ExceptionGuard {
     fn drop(&mut self) {
         // SAFETY: When the `ExceptionGuard` was created the caller promised not to drop it before
@@ -63,8 +70,30 @@ impl Drop for ExceptionGuard {
 ///
 /// Only IRQs, FIQs and SErrors can be masked. Synchronous exceptions cannot be masked and so may
 /// still occur.
-#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+///
+/// If this restores a state where IRQs are unmasked (i.e. it was not called from within another
+/// exception-free section or an exception handler) then it will then run any work deferred on the
+/// current core, via the function registered with
+/// [`set_deferred_work_runner`](crate::set_deferred_work_runner).
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
 pub fn exception_free<T>(f: impl FnOnce(ExceptionFree<'_>) -> T) -> T {
+    let (result, unmasked) = mask_and_run(f);
+    if unmasked {
+        run_deferred_work();
+    }
+    result
+}
+
+/// Runs the given function with exceptions masked, without running any deferred work afterwards.
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+pub(crate) fn exception_free_without_deferred<T>(f: impl FnOnce(ExceptionFree<'_>) -> T) -> T {
+    mask_and_run(f).0
+}
+
+/// Runs the given function with exceptions masked, and returns its result along with whether IRQs
+/// were unmasked again afterwards.
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+fn mask_and_run<T>(f: impl FnOnce(ExceptionFree<'_>) -> T) -> (T, bool) {
     // Mask all exceptions and save previous mask state.
     // SAFETY: We drop the scope guard after the lifetime of the token ends. Any other
     // `ExceptionGuard`s created within `f` will be dropped before `f` returns, ensuring that the
@@ -74,9 +103,10 @@ pub fn exception_free<T>(f: impl FnOnce(ExceptionFree<'_>) -> T) -> T {
     let result = f(token);
 
     // `token` has been dropped by now, as its lifetime prevents `f` from storing it.
+    let unmasked = !scope_guard.prev.irqs_masked();
     drop(scope_guard);
 
-    result
+    (result, unmasked)
 }
 
 /// A token proving that exceptions are currently masked.
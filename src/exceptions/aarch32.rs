@@ -12,6 +12,9 @@ pub struct ExceptionMask(u32);
 /// Mask for the SError interrupt mask, IRQ mask and FIQ mask bits of CPSR.
 const AIF_MASK: u32 = 0x7 << 6;
 
+/// The IRQ mask bit of CPSR.
+const IRQ_MASK: u32 = 1 << 7;
+
 impl ExceptionMask {
     /// Masks IRQs, FIQs, SErrors and Debug exceptions.
     ///
@@ -32,6 +35,11 @@ impl ExceptionMask {
         Self(prev & AIF_MASK)
     }
 
+    /// Returns whether IRQs are masked in this saved mask value.
+    pub fn irqs_masked(self) -> bool {
+        self.0 & IRQ_MASK != 0
+    }
+
     /// Restores the given previous exception mask value.
     ///
     /// # Safety
@@ -9,6 +9,9 @@ use core::arch::asm;
 #[repr(transparent)]
 pub struct ExceptionMask(u64);
 
+/// The IRQ mask bit of DAIF.
+const IRQ_MASK: u64 = 1 << 7;
+
 impl ExceptionMask {
     /// Masks IRQs, FIQs, SErrors and Debug exceptions.
     ///
@@ -29,6 +32,11 @@ impl ExceptionMask {
         Self(prev)
     }
 
+    /// Returns whether IRQs are masked in this saved mask value.
+    pub fn irqs_masked(self) -> bool {
+        self.0 & IRQ_MASK != 0
+    }
+
     /// Restores the given previous exception mask value.
     ///
     /// # Safety
@@ -0,0 +1,39 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Fake exception masking for unit tests on the host, where each thread simulates a CPU core with
+//! its own mask state.
+
+use std::{cell::Cell, thread_local};
+
+thread_local! {
+    static IRQS_MASKED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Exception mask value which has been saved to later be restored.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExceptionMask(bool);
+
+impl ExceptionMask {
+    /// Masks exceptions for the current thread.
+    ///
+    /// Returns the previous mask value, to be passed to [`restore`](Self::restore).
+    pub fn mask() -> Self {
+        Self(IRQS_MASKED.replace(true))
+    }
+
+    /// Returns whether IRQs are masked in this saved mask value.
+    pub fn irqs_masked(self) -> bool {
+        self.0
+    }
+
+    /// Restores the given previous exception mask value.
+    ///
+    /// # Safety
+    ///
+    /// Must not be called while a corresponding `ExceptionFree` token exists.
+    pub unsafe fn restore(self) {
+        IRQS_MASKED.set(self.0);
+    }
+}
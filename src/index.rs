@@ -0,0 +1,154 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{ExceptionFree, ExecutionContext};
+use core::{
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+};
+
+/// The index of the current CPU core or other execution context, obtained once so that it can be
+/// used to access several [`PerCore`](crate::PerCore) values without looking it up again.
+///
+/// This can be obtained from [`ExecutionContext::current`] or [`ExecutionContext::try_current`],
+/// and passed to [`PerCore::get_at`](crate::PerCore::get_at). It is not `Send` or `Sync`, so it
+/// can't be passed to a different core. Like `PerCore::get`, this assumes that the current thread
+/// can't migrate to a different core while it holds the token.
+pub struct CoreIndex<C: ExecutionContext> {
+    index: usize,
+    _cores: PhantomData<(C, *const ())>,
+}
+
+impl<C: ExecutionContext> CoreIndex<C> {
+    /// Returns a token for the given core index.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be the index of the current execution context, as returned by
+    /// `C::context_index()`.
+    pub const unsafe fn new_unchecked(index: usize) -> Self {
+        Self {
+            index,
+            _cores: PhantomData,
+        }
+    }
+
+    /// Returns the index of the current CPU core.
+    pub const fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl<C: ExecutionContext> Clone for CoreIndex<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: ExecutionContext> Copy for CoreIndex<C> {}
+
+impl<C: ExecutionContext> Debug for CoreIndex<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("CoreIndex").field(&self.index).finish()
+    }
+}
+
+/// A [`CoreIndex`] which can only be used while exceptions are masked, as proven by an
+/// [`ExceptionFree`] token with the lifetime `'cs`.
+///
+/// This can be obtained from [`ExecutionContext::current_in`], and passed to
+/// [`PerCore::get_in`](crate::PerCore::get_in). As the current thread can't be preempted while
+/// exceptions are masked, it can't migrate to a different core while it holds the token, and the
+/// references obtained with it can't outlive the exception-free section.
+pub struct MaskedCoreIndex<'cs, C: ExecutionContext> {
+    core: CoreIndex<C>,
+    _token: PhantomData<ExceptionFree<'cs>>,
+}
+
+impl<'cs, C: ExecutionContext> MaskedCoreIndex<'cs, C> {
+    /// Returns a token for the given core index, valid for the lifetime of the given
+    /// `ExceptionFree` token.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be the index of the current execution context, as returned by
+    /// `C::context_index()`.
+    pub const unsafe fn new_unchecked(_: ExceptionFree<'cs>, index: usize) -> Self {
+        Self {
+            // SAFETY: Our caller promises that the index is that of the current execution context.
+            core: unsafe { CoreIndex::new_unchecked(index) },
+            _token: PhantomData,
+        }
+    }
+
+    /// Returns the index of the current CPU core.
+    pub const fn index(self) -> usize {
+        self.core.index()
+    }
+}
+
+impl<C: ExecutionContext> Clone for MaskedCoreIndex<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: ExecutionContext> Copy for MaskedCoreIndex<'_, C> {}
+
+impl<C: ExecutionContext> Debug for MaskedCoreIndex<'_, C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("MaskedCoreIndex")
+            .field(&self.index())
+            .finish()
+    }
+}
+
+/// The index of the current core was not less than the number of values in a
+/// [`PerCore`](crate::PerCore).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CoreIndexOutOfRange {
+    /// The index of the current core.
+    pub index: usize,
+    /// The number of cores for which there are values.
+    pub core_count: usize,
+}
+
+impl Display for CoreIndexOutOfRange {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Core index {} out of range for {} cores",
+            self.index, self.core_count
+        )
+    }
+}
+
+impl core::error::Error for CoreIndexOutOfRange {}
+
+/// An error getting the value for the current core from a [`PerCore`](crate::PerCore).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CoreIndexError {
+    /// [`ExecutionContext::try_context_index`] couldn't determine the index of the current core or
+    /// other execution context, e.g. because its MPIDR wasn't recognised.
+    UnknownCore,
+    /// The index of the current core was out of range.
+    OutOfRange(CoreIndexOutOfRange),
+}
+
+impl Display for CoreIndexError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownCore => write!(f, "Unknown current core"),
+            Self::OutOfRange(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl core::error::Error for CoreIndexError {}
+
+impl From<CoreIndexOutOfRange> for CoreIndexError {
+    fn from(e: CoreIndexOutOfRange) -> Self {
+        Self::OutOfRange(e)
+    }
+}
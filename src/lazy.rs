@@ -0,0 +1,155 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{ExceptionFree, ExceptionLock, ExecutionContext, PerCore};
+use core::cell::OnceCell;
+
+/// A value for each CPU core which is initialised at most once, by the core which owns it.
+///
+/// As each core only ever initialises or accesses its own value, no synchronisation between cores
+/// is needed. Exceptions must be masked while accessing the value, as it may be shared between
+/// exception contexts of the core.
+pub struct PerCoreOnce<T, const CORE_COUNT: usize, C: ExecutionContext> {
+    values: PerCore<[ExceptionLock<OnceCell<T>>; CORE_COUNT], C>,
+}
+
+impl<T, const CORE_COUNT: usize, C: ExecutionContext> PerCoreOnce<T, CORE_COUNT, C> {
+    /// Creates a new set of per-core values, all uninitialised.
+    pub const fn new() -> Self {
+        Self {
+            values: PerCore::new([const { ExceptionLock::new(OnceCell::new()) }; CORE_COUNT]),
+        }
+    }
+
+    /// Returns the value for the current CPU core, or `None` if it hasn't been initialised yet.
+    ///
+    /// Panics if the current core's index is out of range.
+    pub fn get<'cs>(&'cs self, token: ExceptionFree<'cs>) -> Option<&'cs T> {
+        self.values.get().borrow(token).get()
+    }
+
+    /// Initialises the value for the current CPU core to the given value.
+    ///
+    /// Returns the value back as an error if the current core's value was already initialised.
+    ///
+    /// Panics if the current core's index is out of range.
+    pub fn set(&self, token: ExceptionFree, value: T) -> Result<(), T> {
+        self.values.get().borrow(token).set(value)
+    }
+
+    /// Returns the value for the current CPU core, initialising it by calling `f` with the core's
+    /// index if it hasn't been initialised yet.
+    ///
+    /// Panics if the current core's index is out of range, or if `f` tries to initialise the
+    /// current core's value reentrantly.
+    pub fn get_or_init<'cs>(
+        &'cs self,
+        token: ExceptionFree<'cs>,
+        f: impl FnOnce(usize) -> T,
+    ) -> &'cs T {
+        let core = C::current();
+        self.values
+            .get_at(core)
+            .borrow(token)
+            .get_or_init(|| f(core.index()))
+    }
+}
+
+impl<T, const CORE_COUNT: usize, C: ExecutionContext> Default for PerCoreOnce<T, CORE_COUNT, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A value for each CPU core which is initialised by calling `F` with the core's index the first
+/// time the core accesses it.
+///
+/// This is useful for values which can't be constructed in a `const` context, or which depend on
+/// per-core state which isn't ready until the core has booted, such as its GIC redistributor.
+///
+/// ```
+/// use percore::{Cores, ExceptionFree, PerCoreLazy};
+///
+/// struct CoresImpl;
+///
+/// unsafe impl Cores for CoresImpl {
+///     fn core_index() -> usize {
+///         todo!("Return the index of the current CPU core")
+///     }
+/// }
+///
+/// static REDISTRIBUTOR_BASE: PerCoreLazy<usize, 4, CoresImpl> =
+///     PerCoreLazy::new(|core_index| 0x0800_0000 + core_index * 0x2_0000);
+///
+/// fn redistributor_base(token: ExceptionFree) -> usize {
+///     *REDISTRIBUTOR_BASE.get(token)
+/// }
+/// ```
+pub struct PerCoreLazy<T, const CORE_COUNT: usize, C: ExecutionContext, F = fn(usize) -> T> {
+    once: PerCoreOnce<T, CORE_COUNT, C>,
+    init: F,
+}
+
+impl<T, const CORE_COUNT: usize, C: ExecutionContext, F: Fn(usize) -> T>
+    PerCoreLazy<T, CORE_COUNT, C, F>
+{
+    /// Creates a new set of per-core values, which will each be initialised with the given
+    /// function.
+    pub const fn new(init: F) -> Self {
+        Self {
+            once: PerCoreOnce::new(),
+            init,
+        }
+    }
+
+    /// Returns the value for the current CPU core, initialising it first if this is the first time
+    /// it has been accessed.
+    ///
+    /// Panics if the current core's index is out of range, or if the initialisation function tries
+    /// to access the current core's value.
+    pub fn get<'cs>(&'cs self, token: ExceptionFree<'cs>) -> &'cs T {
+        self.once.get_or_init(token, &self.init)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn per_core_once() {
+        static VALUES: PerCoreOnce<u32, 2, FakeCoresImpl> = PerCoreOnce::new();
+
+        FakeCoresImpl::set_core_index(1);
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        assert_eq!(VALUES.get(token), None);
+        assert_eq!(VALUES.set(token, 42), Ok(()));
+        assert_eq!(VALUES.set(token, 43), Err(43));
+        assert_eq!(VALUES.get_or_init(token, |_| unreachable!()), &42);
+
+        FakeCoresImpl::set_core_index(0);
+        assert_eq!(VALUES.get_or_init(token, |index| index as u32 + 10), &10);
+    }
+
+    #[test]
+    fn per_core_lazy() {
+        static INIT_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static VALUES: PerCoreLazy<usize, 2, FakeCoresImpl> = PerCoreLazy::new(|core_index| {
+            INIT_COUNT.fetch_add(1, Ordering::Relaxed);
+            core_index * 100
+        });
+
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        FakeCoresImpl::set_core_index(1);
+        assert_eq!(*VALUES.get(token), 100);
+        assert_eq!(*VALUES.get(token), 100);
+        FakeCoresImpl::set_core_index(0);
+        assert_eq!(*VALUES.get(token), 0);
+        assert_eq!(INIT_COUNT.load(Ordering::Relaxed), 2);
+    }
+}
@@ -62,35 +62,232 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+mod bound;
 #[cfg(feature = "alloc")]
 mod boxed;
+mod coremask;
+mod counter;
+mod crosscall;
+mod deferred;
 mod exceptions;
+mod index;
+mod lazy;
 mod lock;
+mod mailbox;
+mod mpidr;
+mod padded;
+mod preempt;
+mod primary;
+mod queue;
+mod register;
+mod spsc;
+mod storage;
+mod topology;
 
 #[cfg(feature = "derive")]
 pub mod derive;
 
-#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
-pub use self::exceptions::exception_free;
-pub use self::{exceptions::ExceptionFree, lock::ExceptionLock};
-use core::marker::PhantomData;
+#[cfg(target_arch = "arm")]
+pub use self::register::Tpidrprw;
+#[cfg(feature = "alloc")]
+pub use self::spsc::BoxedSpscRing;
+pub use self::{
+    bound::CoreBound,
+    coremask::{AtomicCoreMask, CoreMask, CoreMaskIter},
+    counter::{CounterCell, CounterMode, CounterValue, PerCoreCounter, Saturate, Wrap},
+    crosscall::{CoreAffinity, CrossCall, InboxFull, Ipi},
+    deferred::DeferQueue,
+    exceptions::ExceptionFree,
+    index::{CoreIndex, CoreIndexError, CoreIndexOutOfRange, MaskedCoreIndex},
+    lazy::{PerCoreLazy, PerCoreOnce},
+    lock::ExceptionLock,
+    mailbox::PerCoreMailbox,
+    mpidr::{MpidrCores, MpidrLayout, MpidrList, MpidrTable, Uniform, cluster_mpidrs},
+    padded::{Align64, Align128, CacheAlignment, CachePadded},
+    preempt::{Pinned, PreemptLock, Preemption},
+    primary::{PrimaryCore, PrimaryOnly},
+    register::{CoreIndexAllocator, RegisterCores, RegisterCoresInit, ThreadPointer},
+    spsc::{ArraySpscRing, Slot, SpscConsumer, SpscRing},
+    storage::Storage,
+    topology::{Cluster, Package, PerCluster, PerLevel, PerPackage, Topology, TopologyLevel},
+};
+#[cfg(target_arch = "aarch64")]
+pub use self::{
+    crosscall::GicV3Sgi,
+    register::{TpidrEl1, TpidrEl2},
+};
+#[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+pub use self::{deferred::set_deferred_work_runner, exceptions::exception_free};
+use core::{iter::Sum, marker::PhantomData, mem::MaybeUninit};
 
 /// Trait abstracting how to get the index of the current CPU core.
 ///
 /// # Safety
 ///
 /// `core_index` must never return the same index on different CPU cores.
+///
+/// If `CORE_COUNT` is `Some(n)`, then `core_index` must always return an index less than `n`, and
+/// `core_count` must also return `Some(n)`.
 pub unsafe trait Cores {
+    /// The total number of CPU cores, if it is known at compile time.
+    ///
+    /// If this is set then [`PerCore::new_checked`] can check at compile time that a `PerCore` has
+    /// a value for every core, and [`PerCore::get`] can skip its bounds check.
+    const CORE_COUNT: Option<usize> = None;
+
+    /// The index of the primary core, which boots first and owns any state wrapped in
+    /// [`PrimaryOnly`].
+    const PRIMARY_CORE_INDEX: usize = 0;
+
     /// Returns the index of the current CPU core.
     fn core_index() -> usize;
+
+    /// Returns the total number of CPU cores, if it is known.
+    ///
+    /// The default implementation returns [`CORE_COUNT`](Self::CORE_COUNT). Implementations which
+    /// discover the number of cores at runtime may override it.
+    fn core_count() -> Option<usize> {
+        Self::CORE_COUNT
+    }
+
+    /// Returns the index of the current CPU core, or `None` if it can't be determined.
+    ///
+    /// Implementations which may fail to determine the core index, e.g. because the current core's
+    /// MPIDR isn't recognised, should override this to return `None` rather than panicking. The
+    /// default implementation calls [`core_index`](Self::core_index).
+    ///
+    /// This must return the same index as `core_index` whenever it returns `Some`.
+    fn try_core_index() -> Option<usize> {
+        Some(Self::core_index())
+    }
+}
+
+/// Trait abstracting how to get the index of the current execution context, such as a CPU core, a
+/// vCPU or a green thread.
+///
+/// This is implemented for every type which implements [`Cores`], where each execution context is a
+/// CPU core. [`PerCore`] and [`PerCoreCounter`] can be used with any execution context, e.g. to store
+/// per-vCPU state in a hypervisor, by implementing this trait directly.
+///
+/// # Context switches
+///
+/// Different execution contexts are treated as separate 'threads', like different CPU cores, while
+/// exceptions taken within an execution context are treated as separate exception contexts of it, as
+/// for a core. A context switch, e.g. loading a different vCPU on a physical core, changes the value
+/// returned by `context_index` on that core. References returned by `PerCore::get` for the old
+/// context must not be used after the switch, as they would then refer to the state of a context
+/// which may be running elsewhere.
+///
+/// # Safety
+///
+/// `context_index` must never return the same index for two execution contexts which may be running
+/// at the same time. Context switches must not happen while any reference to a value obtained for
+/// the current context is still live, e.g. from `PerCore::get` or within `exception_free`, and
+/// must ensure that all accesses in one execution context before a switch happen-before any accesses
+/// in the same execution context after it is later resumed, possibly on a different core.
+///
+/// If `CONTEXT_COUNT` is `Some(n)`, then `context_index` must always return an index less than `n`.
+pub unsafe trait ExecutionContext {
+    /// The total number of execution contexts, if it is known at compile time.
+    const CONTEXT_COUNT: Option<usize> = None;
+
+    /// Returns the index of the current execution context.
+    fn context_index() -> usize;
+
+    /// Returns the index of the current execution context, or `None` if it can't be determined.
+    ///
+    /// This must return the same index as `context_index` whenever it returns `Some`.
+    fn try_context_index() -> Option<usize> {
+        Some(Self::context_index())
+    }
+
+    /// Returns a token for the index of the current execution context, which can be used to access
+    /// several `PerCore` values without looking up the index again.
+    fn current() -> CoreIndex<Self>
+    where
+        Self: Sized,
+    {
+        // SAFETY: The index was just returned by `context_index`.
+        unsafe { CoreIndex::new_unchecked(Self::context_index()) }
+    }
+
+    /// Returns a token for the index of the current execution context, or `None` if it can't be
+    /// determined.
+    fn try_current() -> Option<CoreIndex<Self>>
+    where
+        Self: Sized,
+    {
+        // SAFETY: The index was just returned by `try_context_index`, which must be the same as
+        // `context_index`.
+        Self::try_context_index().map(|index| unsafe { CoreIndex::new_unchecked(index) })
+    }
+
+    /// Returns a token for the index of the current execution context which can only be used while
+    /// exceptions are masked, so the current thread can't migrate to a different core while it is
+    /// held.
+    fn current_in(token: ExceptionFree) -> MaskedCoreIndex<Self>
+    where
+        Self: Sized,
+    {
+        // SAFETY: The index was just returned by `context_index`.
+        unsafe { MaskedCoreIndex::new_unchecked(token, Self::context_index()) }
+    }
+}
+
+// SAFETY: Different CPU cores are always running at the same time, and the `Cores` safety contract
+// requires that they have different indices, less than `CORE_COUNT` if it is set. A thread only runs
+// on a single core.
+unsafe impl<C: Cores> ExecutionContext for C {
+    const CONTEXT_COUNT: Option<usize> = C::CORE_COUNT;
+
+    fn context_index() -> usize {
+        C::core_index()
+    }
+
+    fn try_context_index() -> Option<usize> {
+        C::try_core_index()
+    }
 }
 
+/// Marker trait for types which may safely be shared between different exception contexts on the
+/// same CPU core.
+///
+/// [`PerCore`] is [`Sync`] if its values implement this trait, as it only allows each core to access
+/// its own value. [`ExceptionLock`] implements it for any `Send` type, by requiring exceptions to be
+/// masked while accessing its contents.
+///
+/// # Safety
+///
+/// Different exception contexts on the same core must be treated as separate 'threads' for the
+/// purposes of Rust's memory model. Implementations must ensure that a shared reference to the value
+/// can't be used to cause a data race between exception contexts on the same core, as if the type
+/// were `Sync` but only shared between exception contexts of a single core.
+pub unsafe trait CoreSync {}
+
+macro_rules! impl_core_sync_for_atomics {
+    ($width:literal: $($atomic:ident$(<$param:ident>)?),*) => {
+        $(
+            // SAFETY: Atomic types are `Sync`, so they can be shared between exception contexts as
+            // well as cores.
+            #[cfg(target_has_atomic = $width)]
+            unsafe impl$(<$param>)? CoreSync for core::sync::atomic::$atomic$(<$param>)? {}
+        )*
+    };
+}
+
+impl_core_sync_for_atomics!("8": AtomicBool, AtomicI8, AtomicU8);
+impl_core_sync_for_atomics!("16": AtomicI16, AtomicU16);
+impl_core_sync_for_atomics!("32": AtomicI32, AtomicU32);
+impl_core_sync_for_atomics!("64": AtomicI64, AtomicU64);
+impl_core_sync_for_atomics!("ptr": AtomicIsize, AtomicUsize, AtomicPtr<T>);
+
 /// A type which allows values to be stored per CPU core. Only the value associated with the current
 /// CPU core can be accessed.
 ///
-/// To use this type you must first implement the [`Cores`] trait for your platform.
+/// To use this type you must first implement the [`Cores`] trait for your platform, or the
+/// [`ExecutionContext`] trait for other kinds of execution context.
 ///
-/// `C::core_index()` must always return a value less than the length of `V` or there will be a
+/// `C::context_index()` must always return a value less than the length of `V` or there will be a
 /// runtime panic.
 #[derive(Default)]
 #[cfg_attr(
@@ -103,12 +300,12 @@ pub unsafe trait Cores {
     )
 )]
 #[repr(transparent)]
-pub struct PerCore<V: ?Sized, C: Cores> {
+pub struct PerCore<V: ?Sized, C: ExecutionContext> {
     _cores: PhantomData<C>,
     values: V,
 }
 
-impl<V, C: Cores> PerCore<V, C> {
+impl<V, C: ExecutionContext> PerCore<V, C> {
     /// Creates a new set of per-core values.
     pub const fn new(values: V) -> Self {
         Self {
@@ -123,37 +320,224 @@ impl<V, C: Cores> PerCore<V, C> {
     }
 }
 
-impl<T, C: Cores, const CORE_COUNT: usize> PerCore<[T; CORE_COUNT], C> {
+impl<T, C: ExecutionContext, const CORE_COUNT: usize> PerCore<[T; CORE_COUNT], C> {
+    /// Creates a new set of per-core values, checking at compile time that there is one for each
+    /// core.
+    ///
+    /// This fails to compile unless `C::CONTEXT_COUNT` (or `C::CORE_COUNT` for an implementation
+    /// of [`Cores`]) is `Some(CORE_COUNT)`, e.g.:
+    ///
+    /// ```compile_fail
+    /// # use core::sync::atomic::AtomicU32;
+    /// # use percore::{Cores, PerCore};
+    /// struct CoresImpl;
+    ///
+    /// unsafe impl Cores for CoresImpl {
+    ///     const CORE_COUNT: Option<usize> = Some(4);
+    ///
+    ///     fn core_index() -> usize {
+    ///         todo!("Return the index of the current CPU core, less than 4")
+    ///     }
+    /// }
+    ///
+    /// static VALUES: PerCore<[AtomicU32; 2], CoresImpl> =
+    ///     PerCore::new_checked([const { AtomicU32::new(0) }; 2]);
+    /// ```
+    pub const fn new_checked(values: [T; CORE_COUNT]) -> Self {
+        const {
+            assert!(
+                Self::HAS_ALL_CORES,
+                "PerCore length doesn't match ExecutionContext::CONTEXT_COUNT"
+            );
+        }
+        Self::new(values)
+    }
+
+    /// Creates a new set of per-core values, initialising the value for each core by calling `f`
+    /// with the core's index.
+    pub fn from_fn(f: impl FnMut(usize) -> T) -> Self {
+        Self::new(core::array::from_fn(f))
+    }
+
+    /// Creates a new set of per-core values, initialising the value for each core by calling `f`
+    /// with the core's index.
+    ///
+    /// Returns the first error returned by `f`, without calling it for any later cores.
+    pub fn try_from_fn<E>(mut f: impl FnMut(usize) -> Result<T, E>) -> Result<Self, E> {
+        let mut error = None;
+        let values: [Option<T>; CORE_COUNT] = core::array::from_fn(|core_index| {
+            if error.is_some() {
+                return None;
+            }
+            f(core_index).map_err(|e| error = Some(e)).ok()
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(Self::new(values.map(|value| value.unwrap()))),
+        }
+    }
+}
+
+impl<T, C: ExecutionContext> PerCore<&'static mut [T], C> {
+    /// Returns a new `PerCore` using the given memory, such as a region reserved for per-core data
+    /// once the number of cores is known at runtime, initialising the value for each core by calling
+    /// `f` with the core's index.
+    ///
+    /// There is a value for each element of `memory`. The values are never dropped.
+    pub fn new_in(memory: &'static mut [MaybeUninit<T>], mut f: impl FnMut(usize) -> T) -> Self {
+        for (core_index, value) in memory.iter_mut().enumerate() {
+            value.write(f(core_index));
+        }
+        // SAFETY: Every element of `memory` was initialised in the loop above, and `MaybeUninit<T>`
+        // has the same layout as `T`.
+        Self::new(unsafe { &mut *(memory as *mut [MaybeUninit<T>] as *mut [T]) })
+    }
+}
+
+impl<V: Storage, C: ExecutionContext> PerCore<V, C> {
+    /// Whether there is a value for every core, according to `V::LEN` and `C::CONTEXT_COUNT`.
+    const HAS_ALL_CORES: bool =
+        matches!((V::LEN, C::CONTEXT_COUNT), (Some(len), Some(count)) if len == count);
+
     /// Gets a shared reference to the value for the current CPU core.
-    pub fn get(&self) -> &T {
-        &self.values[C::core_index()]
+    ///
+    /// Panics if the current core's index is out of range.
+    pub fn get(&self) -> &V::Item {
+        let values = self.values.as_slice();
+        let index = C::context_index();
+        if Self::HAS_ALL_CORES {
+            // SAFETY: The `ExecutionContext` safety contract requires that the index is less than
+            // `C::CONTEXT_COUNT`, which is the length of the storage.
+            unsafe { values.get_unchecked(index) }
+        } else {
+            &values[index]
+        }
     }
 
     /// Gets a unique reference to the value for the current CPU core.
-    pub fn get_mut(&mut self) -> &mut T {
-        &mut self.values[C::core_index()]
+    ///
+    /// Panics if the current core's index is out of range.
+    pub fn get_mut(&mut self) -> &mut V::Item {
+        let values = self.values.as_mut_slice();
+        let index = C::context_index();
+        if Self::HAS_ALL_CORES {
+            // SAFETY: The `ExecutionContext` safety contract requires that the index is less than
+            // `C::CONTEXT_COUNT`, which is the length of the storage.
+            unsafe { values.get_unchecked_mut(index) }
+        } else {
+            &mut values[index]
+        }
+    }
+
+    /// Gets a shared reference to the value for the current CPU core, using a previously obtained
+    /// core index.
+    ///
+    /// Panics if the core index is out of range.
+    pub fn get_at(&self, core: CoreIndex<C>) -> &V::Item {
+        &self.values.as_slice()[core.index()]
+    }
+
+    /// Gets a shared reference to the value for the current CPU core, using a previously obtained
+    /// core index which is only valid while exceptions are masked.
+    ///
+    /// The reference can't outlive the exception-free section in which the core index was
+    /// obtained.
+    ///
+    /// Panics if the core index is out of range.
+    pub fn get_in<'cs>(&'cs self, core: MaskedCoreIndex<'cs, C>) -> &'cs V::Item {
+        &self.values.as_slice()[core.index()]
+    }
+
+    /// Gets a unique reference to the value for the current CPU core, using a previously obtained
+    /// core index.
+    ///
+    /// Panics if the core index is out of range.
+    pub fn get_mut_at(&mut self, core: CoreIndex<C>) -> &mut V::Item {
+        &mut self.values.as_mut_slice()[core.index()]
+    }
+
+    /// Gets a shared reference to the value for the current CPU core, or an error if the index of
+    /// the current core can't be determined or is out of range.
+    pub fn try_get(&self) -> Result<&V::Item, CoreIndexError> {
+        let values = self.values.as_slice();
+        Ok(&values[Self::try_index(values.len())?])
+    }
+
+    /// Gets a unique reference to the value for the current CPU core, or an error if the index of
+    /// the current core can't be determined or is out of range.
+    pub fn try_get_mut(&mut self) -> Result<&mut V::Item, CoreIndexError> {
+        let values = self.values.as_mut_slice();
+        Ok(&mut values[Self::try_index(values.len())?])
+    }
+
+    /// Returns the index of the current core, if it is less than `core_count`.
+    fn try_index(core_count: usize) -> Result<usize, CoreIndexError> {
+        let index = C::try_context_index().ok_or(CoreIndexError::UnknownCore)?;
+        if index < core_count {
+            Ok(index)
+        } else {
+            Err(CoreIndexOutOfRange { index, core_count }.into())
+        }
     }
 }
 
-// SAFETY: Both different CPU cores and different exception contexts must be treated as separate
-// 'threads' for the purposes of Rust's memory model. `PerCore` only allows access to the value for
-// the current core, and `ExceptionLock` requires exceptions to be disabled while accessing it which
-// prevents concurrent access to its contents from different exception contexts. The combination of
-// the two therefore prevents concurrent access to `T`.
-unsafe impl<T: Send, C: Cores, const CORE_COUNT: usize> Sync
-    for PerCore<[ExceptionLock<T>; CORE_COUNT], C>
+impl<V: Storage, C: ExecutionContext> PerCore<V, C>
+where
+    V::Item: Sync,
 {
+    /// Gets a shared reference to the value for the CPU core with the given index, or `None` if
+    /// there is no value for that index.
+    ///
+    /// This is only available for values which can be safely accessed from any core, such as
+    /// atomics.
+    pub fn get_for(&self, core_index: usize) -> Option<&V::Item> {
+        self.values.as_slice().get(core_index)
+    }
+
+    /// Returns an iterator over the values for all CPU cores, in order of core index.
+    ///
+    /// This is only available for values which can be safely accessed from any core, such as
+    /// atomics.
+    pub fn iter(&self) -> core::slice::Iter<'_, V::Item> {
+        self.values.as_slice().iter()
+    }
+
+    /// Folds the values for all CPU cores into a single value, in order of core index.
+    pub fn fold<B>(&self, init: B, f: impl FnMut(B, &V::Item) -> B) -> B {
+        self.iter().fold(init, f)
+    }
+
+    /// Sums the result of applying the given function to the value for each CPU core.
+    ///
+    /// For example, `counts.sum(|count| count.load(Ordering::Relaxed))` gives the total of a set of
+    /// per-core atomic counters.
+    pub fn sum<S: Sum<R>, R>(&self, f: impl FnMut(&V::Item) -> R) -> S {
+        self.iter().map(f).sum()
+    }
 }
 
+// SAFETY: Both different CPU cores and different exception contexts must be treated as separate
+// 'threads' for the purposes of Rust's memory model. `PerCore` only allows access to the value for
+// the current core, and `CoreSync` requires that the value may be safely shared between different
+// exception contexts on the same core. The combination of the two therefore prevents concurrent
+// access to the values. The `Storage` safety contract requires that `as_slice` may be called
+// concurrently.
+unsafe impl<V: Storage, C: ExecutionContext> Sync for PerCore<V, C> where V::Item: CoreSync {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use core::cell::RefCell;
-    use std::sync::Mutex;
+    use core::{
+        cell::RefCell,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+    use std::{cell::Cell, thread, thread_local};
 
-    /// Index of the current simulated core. Must be set to an actual number before accessing a
-    /// PerCore variable.
-    static CORE_INDEX: Mutex<Option<usize>> = Mutex::new(None);
+    thread_local! {
+        /// Index of the current simulated core. Must be set to an actual number before accessing a
+        /// PerCore variable.
+        static CORE_INDEX: Cell<Option<usize>> = const { Cell::new(None) };
+    }
 
     /// A Fake implementation of `Cores` for tests, which will return the value set by
     /// `set_core_index`.
@@ -162,17 +546,18 @@ mod tests {
     impl FakeCoresImpl {
         /// Sets the fake core index for this thread.
         pub fn set_core_index(core_index: usize) {
-            *CORE_INDEX.lock().unwrap() = Some(core_index);
+            CORE_INDEX.set(Some(core_index));
         }
     }
 
     // SAFETY: These tests are all run on a single core.
     unsafe impl Cores for FakeCoresImpl {
         fn core_index() -> usize {
-            CORE_INDEX
-                .lock()
-                .unwrap()
-                .expect("CORE_INDEX not set in test")
+            CORE_INDEX.get().expect("CORE_INDEX not set in test")
+        }
+
+        fn try_core_index() -> Option<usize> {
+            CORE_INDEX.get()
         }
     }
 
@@ -209,6 +594,161 @@ mod tests {
         }
     }
 
+    struct TwoCores;
+
+    // SAFETY: The index is always 0 or 1, and these tests are all run on a single core.
+    unsafe impl Cores for TwoCores {
+        const CORE_COUNT: Option<usize> = Some(2);
+
+        fn core_index() -> usize {
+            FakeCoresImpl::core_index() % 2
+        }
+    }
+
+    #[test]
+    fn new_checked() {
+        static VALUES: PerCore<[AtomicU32; 2], TwoCores> =
+            PerCore::new_checked([AtomicU32::new(10), AtomicU32::new(11)]);
+
+        assert_eq!(TwoCores::core_count(), Some(2));
+        FakeCoresImpl::set_core_index(1);
+        assert_eq!(VALUES.get().load(Ordering::Relaxed), 11);
+        FakeCoresImpl::set_core_index(0);
+        assert_eq!(VALUES.get().load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn from_fn() {
+        let values = PerCore::<[usize; 3], FakeCoresImpl>::from_fn(|core_index| core_index * 10);
+        assert_eq!(values.into_inner(), [0, 10, 20]);
+
+        let mut calls = 0;
+        let result = PerCore::<[usize; 3], FakeCoresImpl>::try_from_fn(|core_index| {
+            calls += 1;
+            if core_index == 1 {
+                Err("no stack")
+            } else {
+                Ok(core_index)
+            }
+        });
+        assert_eq!(result.err(), Some("no stack"));
+        assert_eq!(calls, 2);
+        assert_eq!(
+            PerCore::<[usize; 2], FakeCoresImpl>::try_from_fn(Ok::<_, ()>)
+                .unwrap()
+                .into_inner(),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn get_at() {
+        static STATE: PerCore<[ExceptionLock<RefCell<u32>>; 2], FakeCoresImpl> =
+            PerCore::new([const { ExceptionLock::new(RefCell::new(0)) }; 2]);
+        let mut counts = PerCore::<_, FakeCoresImpl>::new([0, 0]);
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        FakeCoresImpl::set_core_index(1);
+        let core = FakeCoresImpl::current();
+        assert_eq!(core.index(), 1);
+        *STATE.get_at(core).borrow_mut(token) += 1;
+        *counts.get_mut_at(core) += 1;
+        assert_eq!(*STATE.get().borrow_mut(token), 1);
+        assert_eq!(counts.into_inner(), [0, 1]);
+
+        let core = FakeCoresImpl::current_in(token);
+        assert_eq!(core.index(), 1);
+        *STATE.get_in(core).borrow_mut(token) += 1;
+        assert_eq!(*STATE.get().borrow_mut(token), 2);
+    }
+
+    /// Fake vCPUs, where the current vCPU is given by the simulated core.
+    struct FakeVcpus;
+
+    // SAFETY: Each simulated core only runs a single vCPU, and vCPUs are never switched.
+    unsafe impl ExecutionContext for FakeVcpus {
+        fn context_index() -> usize {
+            FakeCoresImpl::core_index() + 1
+        }
+    }
+
+    #[test]
+    fn per_vcpu() {
+        static VCPU_STATE: PerCore<[ExceptionLock<RefCell<u32>>; 3], FakeVcpus> =
+            PerCore::new([const { ExceptionLock::new(RefCell::new(0)) }; 3]);
+        static VCPU_EXITS: PerCoreCounter<u32, 3, FakeVcpus> = PerCoreCounter::new();
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        FakeCoresImpl::set_core_index(1);
+        *VCPU_STATE.get().borrow_mut(token) = 42;
+        VCPU_EXITS.increment();
+        assert_eq!(
+            *VCPU_STATE.get_at(FakeVcpus::current()).borrow_mut(token),
+            42
+        );
+        assert_eq!(VCPU_EXITS.get_for(2), Some(1));
+    }
+
+    #[test]
+    fn try_get() {
+        let mut values = PerCore::<_, FakeCoresImpl>::new([1, 2]);
+
+        thread::spawn(move || {
+            assert_eq!(values.try_get(), Err(CoreIndexError::UnknownCore));
+            FakeCoresImpl::set_core_index(1);
+            assert_eq!(values.try_get(), Ok(&2));
+            *values.try_get_mut().unwrap() = 3;
+            FakeCoresImpl::set_core_index(2);
+            assert_eq!(
+                values.try_get_mut(),
+                Err(CoreIndexError::OutOfRange(CoreIndexOutOfRange {
+                    index: 2,
+                    core_count: 2
+                }))
+            );
+            assert_eq!(values.into_inner(), [1, 3]);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn remote_access() {
+        static COUNTS: PerCore<[AtomicU32; 3], FakeCoresImpl> =
+            PerCore::new([const { AtomicU32::new(0) }; 3]);
+
+        for core_index in 0..3 {
+            thread::spawn(move || {
+                FakeCoresImpl::set_core_index(core_index);
+                COUNTS
+                    .get()
+                    .fetch_add(core_index as u32 + 1, Ordering::Relaxed);
+            })
+            .join()
+            .unwrap();
+        }
+
+        assert_eq!(COUNTS.get_for(2).unwrap().load(Ordering::Relaxed), 3);
+        assert!(COUNTS.get_for(3).is_none());
+        assert_eq!(
+            COUNTS
+                .iter()
+                .map(|count| count.load(Ordering::Relaxed))
+                .collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            COUNTS.fold(0, |max, count| max.max(count.load(Ordering::Relaxed))),
+            3
+        );
+        assert_eq!(
+            COUNTS.sum::<u32, _>(|count| count.load(Ordering::Relaxed)),
+            6
+        );
+    }
+
     #[test]
     fn exception_lock_into_inner() {
         let lock = ExceptionLock::new(42u32);
@@ -2,7 +2,7 @@
 // This project is dual-licensed under Apache 2.0 and MIT terms.
 // See LICENSE-APACHE and LICENSE-MIT for details.
 
-use crate::ExceptionFree;
+use crate::{CoreSync, ExceptionFree};
 use core::cell::{RefCell, RefMut};
 
 /// Allows access to the given value only while exceptions are masked, allowing it to be shared
@@ -54,3 +54,8 @@ impl<T> ExceptionLock<RefCell<T>> {
         self.value.as_ptr()
     }
 }
+
+// SAFETY: `ExceptionLock` requires exceptions to be masked while accessing its contents, which
+// prevents concurrent access from different exception contexts on the same core. `T` must be `Send`
+// because the value may still be accessed from different exception contexts at different times.
+unsafe impl<T: Send> CoreSync for ExceptionLock<T> {}
@@ -0,0 +1,194 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CoreSync, Cores, ExceptionFree, PerCore, queue::ArrayQueue};
+use core::{
+    cell::UnsafeCell,
+    hint::spin_loop,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// A fixed-capacity queue of messages for a single core, protected by a spinlock.
+struct Inbox<T, const CAP: usize> {
+    locked: AtomicBool,
+    queue: UnsafeCell<ArrayQueue<T, CAP>>,
+}
+
+impl<T, const CAP: usize> Inbox<T, CAP> {
+    const fn new() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            queue: UnsafeCell::new(ArrayQueue::new()),
+        }
+    }
+
+    /// Runs the given function with the inbox locked.
+    ///
+    /// The token proves that exceptions are masked, so an exception handler on the same core can't
+    /// deadlock by trying to take the lock while it is held.
+    fn with_lock<R>(
+        &self,
+        _token: ExceptionFree,
+        f: impl FnOnce(&mut ArrayQueue<T, CAP>) -> R,
+    ) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            spin_loop();
+        }
+        // Release the lock even if `f` panics.
+        let _guard = InboxGuard {
+            locked: &self.locked,
+        };
+        // SAFETY: We hold the lock, so nothing else can access the queue until we release it.
+        f(unsafe { &mut *self.queue.get() })
+    }
+}
+
+/// Releases the lock of an [`Inbox`] when it is dropped.
+struct InboxGuard<'a> {
+    locked: &'a AtomicBool,
+}
+
+impl Drop for InboxGuard<'_> {
+    fn drop(&mut self) {
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+// SAFETY: The queue is only accessed while holding the spinlock, so messages are only accessed from
+// one core or exception context at a time.
+unsafe impl<T: Send, const CAP: usize> Sync for Inbox<T, CAP> {}
+
+// SAFETY: `Inbox` is `Sync`, so it can be shared between exception contexts as well as cores.
+unsafe impl<T: Send, const CAP: usize> CoreSync for Inbox<T, CAP> {}
+
+/// A set of per-core inboxes, for sending messages from any core to any other core.
+///
+/// Any core can [`send`](Self::send) a message to any core's inbox, but each core can only
+/// [`receive`](Self::receive) messages from its own inbox. Each inbox holds up to `CAP` messages.
+///
+/// The inboxes are protected by spinlocks, which are only held with exceptions masked so that it
+/// is safe to send and receive messages from exception handlers too.
+pub struct PerCoreMailbox<T, const CAP: usize, const CORE_COUNT: usize, C: Cores> {
+    inboxes: PerCore<[Inbox<T, CAP>; CORE_COUNT], C>,
+    notifier: Option<fn(usize)>,
+}
+
+impl<T, const CAP: usize, const CORE_COUNT: usize, C: Cores> PerCoreMailbox<T, CAP, CORE_COUNT, C> {
+    /// Creates a new set of empty inboxes.
+    pub const fn new() -> Self {
+        Self {
+            inboxes: PerCore::new([const { Inbox::new() }; CORE_COUNT]),
+            notifier: None,
+        }
+    }
+
+    /// Creates a new set of empty inboxes, which will call the given function with the index of
+    /// the destination core after each message is sent.
+    ///
+    /// This can be used to raise an inter-processor interrupt to let the destination core know that
+    /// it has a message waiting.
+    pub const fn with_notifier(notifier: fn(usize)) -> Self {
+        Self {
+            inboxes: PerCore::new([const { Inbox::new() }; CORE_COUNT]),
+            notifier: Some(notifier),
+        }
+    }
+
+    /// Sends the given message to the inbox of the core with the given index.
+    ///
+    /// Returns the message back as an error if the destination inbox is full. The notifier (if any)
+    /// is called after the message has been added to the inbox.
+    ///
+    /// Panics if `core_index` is not less than `CORE_COUNT`.
+    pub fn send(&self, token: ExceptionFree, core_index: usize, message: T) -> Result<(), T> {
+        self.inboxes.values[core_index].with_lock(token, |queue| queue.push(message))?;
+        if let Some(notifier) = self.notifier {
+            notifier(core_index);
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the oldest message from the current core's inbox, if any.
+    pub fn receive(&self, token: ExceptionFree) -> Option<T> {
+        self.inboxes.get().with_lock(token, ArrayQueue::pop)
+    }
+}
+
+impl<T, const CAP: usize, const CORE_COUNT: usize, C: Cores> Default
+    for PerCoreMailbox<T, CAP, CORE_COUNT, C>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use core::sync::atomic::AtomicUsize;
+    use std::{
+        panic::{AssertUnwindSafe, catch_unwind},
+        thread,
+    };
+
+    #[test]
+    fn send_to_other_core() {
+        static MAILBOX: PerCoreMailbox<u32, 2, 2, FakeCoresImpl> = PerCoreMailbox::new();
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        FakeCoresImpl::set_core_index(0);
+        assert_eq!(MAILBOX.send(token, 1, 10), Ok(()));
+        assert_eq!(MAILBOX.send(token, 1, 11), Ok(()));
+        assert_eq!(MAILBOX.send(token, 1, 12), Err(12));
+        assert_eq!(MAILBOX.receive(token), None);
+
+        thread::spawn(|| {
+            // SAFETY: There are no exceptions in the simulated environment of the tests.
+            let token = unsafe { ExceptionFree::new() };
+            FakeCoresImpl::set_core_index(1);
+            assert_eq!(MAILBOX.receive(token), Some(10));
+            assert_eq!(MAILBOX.receive(token), Some(11));
+            assert_eq!(MAILBOX.receive(token), None);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn notifier() {
+        static NOTIFIED: AtomicUsize = AtomicUsize::new(usize::MAX);
+        static MAILBOX: PerCoreMailbox<u32, 2, 4, FakeCoresImpl> =
+            PerCoreMailbox::with_notifier(|core_index| {
+                NOTIFIED.store(core_index, Ordering::SeqCst)
+            });
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        FakeCoresImpl::set_core_index(0);
+        MAILBOX.send(token, 3, 42).unwrap();
+        assert_eq!(NOTIFIED.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn unlock_on_panic() {
+        let inbox = Inbox::<u32, 2>::new();
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        assert!(
+            catch_unwind(AssertUnwindSafe(|| {
+                inbox.with_lock(token, |_| panic!("Failed while locked"))
+            }))
+            .is_err()
+        );
+        assert!(!inbox.locked.load(Ordering::Relaxed));
+        assert_eq!(inbox.with_lock(token, |queue| queue.push(1)), Ok(()));
+    }
+}
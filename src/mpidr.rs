@@ -0,0 +1,346 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::CoreAffinity;
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+use crate::Cores;
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+use core::arch::asm;
+use core::marker::PhantomData;
+
+/// The affinity fields of MPIDR: Aff0, Aff1, Aff2 and Aff3.
+const MPIDR_AFFINITY_MASK: u64 = 0xff_00ff_ffff;
+
+/// Describes how the MPIDR affinity values of the cores in a system map to dense core indices.
+///
+/// [`Uniform`] and [`MpidrTable`] implement this for common layouts.
+///
+/// # Safety
+///
+/// `index_for_mpidr` must never return the same index for different MPIDR values, and must always
+/// return an index less than `CORE_COUNT`. `mpidr_for_index` must be its inverse.
+pub unsafe trait MpidrLayout {
+    /// The total number of cores.
+    const CORE_COUNT: usize;
+
+    /// Returns the dense index of the core with the given MPIDR affinity value, or `None` if it is
+    /// not part of the layout.
+    ///
+    /// Only the affinity fields of `mpidr` are used.
+    fn index_for_mpidr(mpidr: u64) -> Option<usize>;
+
+    /// Returns the MPIDR affinity value of the core with the given index.
+    ///
+    /// Panics if `index` is not less than `CORE_COUNT`.
+    fn mpidr_for_index(index: usize) -> u64;
+}
+
+/// A layout of `CLUSTERS` clusters, each with `CORES` cores, each with `THREADS` hardware threads.
+///
+/// `MT` must match the MT bit of the cores' MPIDR values, which determines the affinity level of
+/// each field. If it is `false` then the cluster is in Aff1 and the core in Aff0, as on older cores
+/// such as Cortex-A53 or Cortex-A57. If it is `true` then the cluster is in Aff2, the core in Aff1
+/// and the thread in Aff0. This is the case for DynamIQ cores such as Cortex-A55, Cortex-A76 and
+/// Neoverse N1, which set the MT bit even though they only have a single thread, so Aff0 is always
+/// 0. `THREADS` may only be more than 1 if `MT` is `true`.
+///
+/// Cores are numbered in order of cluster, then core, then thread.
+///
+/// ```
+/// use percore::{MpidrLayout, Uniform};
+///
+/// /// A DynamIQ cluster of four Cortex-A76 cores.
+/// type Layout = Uniform<1, 4, 1, true>;
+///
+/// assert_eq!(Layout::index_for_mpidr(0x8100_0200), Some(2));
+/// ```
+pub struct Uniform<
+    const CLUSTERS: usize,
+    const CORES: usize,
+    const THREADS: usize = 1,
+    const MT: bool = false,
+>;
+
+impl<const CLUSTERS: usize, const CORES: usize, const THREADS: usize, const MT: bool>
+    Uniform<CLUSTERS, CORES, THREADS, MT>
+{
+    const VALID: () = {
+        assert!(
+            CLUSTERS > 0
+                && CLUSTERS <= 256
+                && CORES > 0
+                && CORES <= 256
+                && THREADS > 0
+                && THREADS <= 256,
+            "Each level of a uniform MPIDR layout must have between 1 and 256 entries"
+        );
+        assert!(
+            MT || THREADS == 1,
+            "A uniform MPIDR layout with multiple threads per core must have MT set"
+        );
+    };
+
+    /// The position of the lowest bit of the affinity field for the cluster.
+    const CLUSTER_SHIFT: u32 = if MT { 16 } else { 8 };
+
+    /// The position of the lowest bit of the affinity field for the core.
+    const CORE_SHIFT: u32 = if MT { 8 } else { 0 };
+}
+
+// SAFETY: `index_for_mpidr` checks that each affinity field is in range and that the unused fields
+// are zero, so different MPIDR values give different indices, all less than `CORE_COUNT`.
+unsafe impl<const CLUSTERS: usize, const CORES: usize, const THREADS: usize, const MT: bool>
+    MpidrLayout for Uniform<CLUSTERS, CORES, THREADS, MT>
+{
+    const CORE_COUNT: usize = CLUSTERS * CORES * THREADS;
+
+    fn index_for_mpidr(mpidr: u64) -> Option<usize> {
+        let () = Self::VALID;
+        let mpidr = mpidr & MPIDR_AFFINITY_MASK;
+        let field = |shift: u32| (mpidr >> shift) as usize & 0xff;
+
+        let cluster = field(Self::CLUSTER_SHIFT);
+        let core = field(Self::CORE_SHIFT);
+        let thread = if MT { field(0) } else { 0 };
+        let used_mask = (0xff << Self::CLUSTER_SHIFT) | 0xffff;
+        if mpidr & !used_mask != 0 || cluster >= CLUSTERS || core >= CORES || thread >= THREADS {
+            return None;
+        }
+        Some((cluster * CORES + core) * THREADS + thread)
+    }
+
+    fn mpidr_for_index(index: usize) -> u64 {
+        let () = Self::VALID;
+        assert!(index < Self::CORE_COUNT);
+        let thread = index % THREADS;
+        let core = index / THREADS % CORES;
+        let cluster = index / THREADS / CORES;
+        ((cluster as u64) << Self::CLUSTER_SHIFT)
+            | ((core as u64) << Self::CORE_SHIFT)
+            | thread as u64
+    }
+}
+
+/// A list of the MPIDR affinity values of all the cores in a system, for use with [`MpidrTable`].
+pub trait MpidrList {
+    /// The MPIDR affinity value of each core, in order of core index.
+    ///
+    /// For irregular layouts this can be generated with [`cluster_mpidrs`].
+    const MPIDRS: &'static [u64];
+}
+
+/// A layout given by an explicit table of MPIDR affinity values, for irregular systems.
+///
+/// The index of each core is the position of its MPIDR value in `L::MPIDRS`. The values are checked
+/// for uniqueness at compile time.
+pub struct MpidrTable<L: MpidrList>(PhantomData<L>);
+
+impl<L: MpidrList> MpidrTable<L> {
+    const VALID: () = assert!(
+        all_unique_affinities(L::MPIDRS),
+        "MPIDR table contains duplicate affinity values"
+    );
+}
+
+// SAFETY: The affinity values in the table are checked to be unique, so each index is only returned
+// for a single MPIDR value, and the index is always less than the length of the table.
+unsafe impl<L: MpidrList> MpidrLayout for MpidrTable<L> {
+    const CORE_COUNT: usize = L::MPIDRS.len();
+
+    fn index_for_mpidr(mpidr: u64) -> Option<usize> {
+        let () = Self::VALID;
+        let mpidr = mpidr & MPIDR_AFFINITY_MASK;
+        L::MPIDRS
+            .iter()
+            .position(|&entry| entry & MPIDR_AFFINITY_MASK == mpidr)
+    }
+
+    fn mpidr_for_index(index: usize) -> u64 {
+        let () = Self::VALID;
+        L::MPIDRS[index] & MPIDR_AFFINITY_MASK
+    }
+}
+
+/// Returns whether the affinity fields of all the given MPIDR values are different.
+const fn all_unique_affinities(mpidrs: &[u64]) -> bool {
+    let mut i = 0;
+    while i < mpidrs.len() {
+        let mut j = i + 1;
+        while j < mpidrs.len() {
+            if mpidrs[i] & MPIDR_AFFINITY_MASK == mpidrs[j] & MPIDR_AFFINITY_MASK {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Generates a table of MPIDR affinity values for a system with clusters of different sizes, e.g.
+/// for a big.LITTLE system.
+///
+/// `cores_per_cluster[i]` is the number of cores in cluster `i`. Each core's MPIDR value has the
+/// cluster in Aff1 and the core within the cluster in Aff0. `N` must be the total number of cores.
+///
+/// ```
+/// use percore::{MpidrList, cluster_mpidrs};
+///
+/// struct BigLittle;
+///
+/// impl MpidrList for BigLittle {
+///     const MPIDRS: &'static [u64] = &cluster_mpidrs::<6>(&[4, 2]);
+/// }
+///
+/// assert_eq!(BigLittle::MPIDRS, [0x000, 0x001, 0x002, 0x003, 0x100, 0x101]);
+/// ```
+pub const fn cluster_mpidrs<const N: usize>(cores_per_cluster: &[usize]) -> [u64; N] {
+    let mut mpidrs = [0; N];
+    let mut index = 0;
+    let mut cluster = 0;
+    while cluster < cores_per_cluster.len() {
+        assert!(cluster < 256 && cores_per_cluster[cluster] <= 256);
+        let mut core = 0;
+        while core < cores_per_cluster[cluster] {
+            assert!(index < N, "Too many cores for MPIDR table");
+            mpidrs[index] = ((cluster as u64) << 8) | core as u64;
+            index += 1;
+            core += 1;
+        }
+        cluster += 1;
+    }
+    assert!(index == N, "Too few cores for MPIDR table");
+    mpidrs
+}
+
+/// An implementation of [`Cores`](crate::Cores) which reads the current core's MPIDR and maps it
+/// to a dense index with the given layout.
+///
+/// `core_index` panics if the current core's MPIDR isn't part of the layout, while `try_core_index`
+/// returns `None`. It also implements [`CoreAffinity`], for use with `GicV3Sgi` on AArch64.
+///
+/// ```
+/// use percore::{MpidrCores, Uniform};
+///
+/// /// Two clusters of four cores each.
+/// type CoresImpl = MpidrCores<Uniform<2, 4>>;
+/// ```
+pub struct MpidrCores<L: MpidrLayout>(PhantomData<L>);
+
+// SAFETY: Each core has a different MPIDR affinity value, and the `MpidrLayout` safety contract
+// requires that different values map to different indices, less than `CORE_COUNT`.
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+unsafe impl<L: MpidrLayout> Cores for MpidrCores<L> {
+    const CORE_COUNT: Option<usize> = Some(L::CORE_COUNT);
+
+    fn core_index() -> usize {
+        Self::try_core_index().expect("Current core's MPIDR not found in layout")
+    }
+
+    fn try_core_index() -> Option<usize> {
+        L::index_for_mpidr(read_mpidr())
+    }
+}
+
+impl<L: MpidrLayout> CoreAffinity for MpidrCores<L> {
+    fn mpidr(core_index: usize) -> u64 {
+        L::mpidr_for_index(core_index)
+    }
+}
+
+/// Returns the value of the MPIDR register of the current core.
+#[cfg(target_arch = "aarch64")]
+pub(crate) fn read_mpidr() -> u64 {
+    let mpidr: u64;
+    // SAFETY: Reading MPIDR_EL1 doesn't access memory or have any side effects.
+    unsafe {
+        asm!("mrs {}, mpidr_el1", out(reg) mpidr, options(nomem, nostack, preserves_flags));
+    }
+    mpidr
+}
+
+/// Returns the value of the MPIDR register of the current core.
+#[cfg(target_arch = "arm")]
+pub(crate) fn read_mpidr() -> u64 {
+    let mpidr: u32;
+    // SAFETY: Reading MPIDR doesn't access memory or have any side effects.
+    unsafe {
+        asm!("mrc p15, 0, {}, c0, c0, 5", out(reg) mpidr, options(nomem, nostack, preserves_flags));
+    }
+    mpidr.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform() {
+        type Layout = Uniform<2, 4>;
+        assert_eq!(Layout::CORE_COUNT, 8);
+        assert_eq!(Layout::index_for_mpidr(0x8000_0000), Some(0));
+        assert_eq!(Layout::index_for_mpidr(0x0000_0103), Some(7));
+        assert_eq!(Layout::index_for_mpidr(0x0000_0104), None);
+        assert_eq!(Layout::index_for_mpidr(0x0000_0200), None);
+        assert_eq!(Layout::index_for_mpidr(0x0001_0000), None);
+        for index in 0..Layout::CORE_COUNT {
+            assert_eq!(
+                Layout::index_for_mpidr(Layout::mpidr_for_index(index)),
+                Some(index)
+            );
+        }
+    }
+
+    #[test]
+    fn uniform_threads() {
+        type Layout = Uniform<2, 2, 2, true>;
+        assert_eq!(Layout::CORE_COUNT, 8);
+        assert_eq!(Layout::index_for_mpidr(0x0100_0001), Some(1));
+        assert_eq!(Layout::index_for_mpidr(0x0001_0100), Some(6));
+        assert_eq!(Layout::mpidr_for_index(5), 0x0001_0001);
+        assert_eq!(Layout::index_for_mpidr(0x0000_0002), None);
+    }
+
+    #[test]
+    fn uniform_dynamiq() {
+        type Layout = Uniform<2, 4, 1, true>;
+        assert_eq!(Layout::CORE_COUNT, 8);
+        assert_eq!(Layout::index_for_mpidr(0x8100_0000), Some(0));
+        assert_eq!(Layout::index_for_mpidr(0x8100_0100), Some(1));
+        assert_eq!(Layout::index_for_mpidr(0x8101_0000), Some(4));
+        assert_eq!(Layout::index_for_mpidr(0x8101_0300), Some(7));
+        assert_eq!(Layout::index_for_mpidr(0x8100_0001), None);
+        assert_eq!(Layout::index_for_mpidr(0x8100_0400), None);
+        assert_eq!(Layout::index_for_mpidr(0x8102_0000), None);
+        assert_eq!(Layout::mpidr_for_index(5), 0x0001_0100);
+        for index in 0..Layout::CORE_COUNT {
+            assert_eq!(
+                Layout::index_for_mpidr(Layout::mpidr_for_index(index)),
+                Some(index)
+            );
+        }
+    }
+
+    #[test]
+    fn table() {
+        struct Irregular;
+
+        impl MpidrList for Irregular {
+            const MPIDRS: &'static [u64] = &[0x100, 0x1_0000_0000, 0x0, 0x8000_0001];
+        }
+
+        type Layout = MpidrTable<Irregular>;
+        assert_eq!(Layout::CORE_COUNT, 4);
+        assert_eq!(Layout::index_for_mpidr(0x8000_0000), Some(2));
+        assert_eq!(Layout::index_for_mpidr(0x1_0000_0000), Some(1));
+        assert_eq!(Layout::index_for_mpidr(0x2), None);
+        assert_eq!(MpidrCores::<Layout>::mpidr(3), 0x1);
+    }
+
+    #[test]
+    fn unique() {
+        assert!(all_unique_affinities(&cluster_mpidrs::<5>(&[2, 3])));
+        assert!(!all_unique_affinities(&[0x1, 0x4000_0001]));
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CoreSync, ExecutionContext, PerCore};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use core::iter::repeat_with;
+use core::{
+    fmt::Debug,
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An alignment for [`CachePadded`]: either [`Align64`] or [`Align128`].
+pub trait CacheAlignment: sealed::Sealed + Clone + Copy + Debug + Default + Eq + PartialEq {}
+
+/// 64 byte alignment, the size of a cache line on most Arm cores.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(zerocopy::FromBytes, zerocopy::Immutable, zerocopy::KnownLayout)
+)]
+#[repr(C, align(64))]
+pub struct Align64;
+
+impl sealed::Sealed for Align64 {}
+impl CacheAlignment for Align64 {}
+
+/// 128 byte alignment, to avoid false sharing on cores such as Neoverse which prefetch pairs of
+/// cache lines.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(zerocopy::FromBytes, zerocopy::Immutable, zerocopy::KnownLayout)
+)]
+#[repr(C, align(128))]
+pub struct Align128;
+
+impl sealed::Sealed for Align128 {}
+impl CacheAlignment for Align128 {}
+
+/// Pads and aligns a value to the given alignment, which defaults to a 64 byte cache line.
+///
+/// Storing each core's value in a `CachePadded` ensures that values for different cores are in
+/// different cache lines, so that cores accessing their own values don't contend with each other
+/// for the same cache line.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(zerocopy::FromBytes, zerocopy::Immutable, zerocopy::KnownLayout)
+)]
+#[repr(C)]
+pub struct CachePadded<T, A: CacheAlignment = Align64> {
+    _alignment: [A; 0],
+    value: T,
+}
+
+impl<T, A: CacheAlignment> CachePadded<T, A> {
+    /// Pads and aligns the given value.
+    pub const fn new(value: T) -> Self {
+        Self {
+            _alignment: [],
+            value,
+        }
+    }
+
+    /// Consumes the `CachePadded`, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, A: CacheAlignment> Deref for CachePadded<T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, A: CacheAlignment> DerefMut for CachePadded<T, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T, A: CacheAlignment> From<T> for CachePadded<T, A> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+// SAFETY: `CachePadded` only adds padding, so it can be shared between exception contexts whenever
+// `T` can.
+unsafe impl<T: CoreSync, A: CacheAlignment> CoreSync for CachePadded<T, A> {}
+
+impl<T, A: CacheAlignment, C: ExecutionContext, const CORE_COUNT: usize>
+    PerCore<[CachePadded<T, A>; CORE_COUNT], C>
+{
+    /// Creates a new set of per-core values, with each value padded to its own cache line.
+    pub const fn new_padded(values: [T; CORE_COUNT]) -> Self {
+        let values = ManuallyDrop::new(values);
+        let values = (&raw const values).cast::<T>();
+        let mut padded = [const { MaybeUninit::<CachePadded<T, A>>::uninit() }; CORE_COUNT];
+        let mut i = 0;
+        while i < CORE_COUNT {
+            // SAFETY: `i` is in bounds of the original array, and each element is only read once.
+            // The array is wrapped in `ManuallyDrop` so the elements won't be dropped again.
+            padded[i] = MaybeUninit::new(CachePadded::new(unsafe { values.add(i).read() }));
+            i += 1;
+        }
+        // SAFETY: Every element of `padded` was initialised in the loop above, and
+        // `MaybeUninit<T>` has the same layout as `T`.
+        Self::new(unsafe {
+            (&raw const padded)
+                .cast::<[CachePadded<T, A>; CORE_COUNT]>()
+                .read()
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Default, A: CacheAlignment, C: ExecutionContext> PerCore<Box<[CachePadded<T, A>]>, C> {
+    /// Returns a new `PerCore` wrapping a boxed slice of `core_count` elements, each initialised to
+    /// the default value of `T` and padded to its own cache line.
+    pub fn new_padded_with_default(core_count: usize) -> Self {
+        Self::new(
+            repeat_with(|| CachePadded::new(T::default()))
+                .take(core_count)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExceptionFree, ExceptionLock, tests::FakeCoresImpl};
+    use core::{
+        cell::RefCell,
+        mem::{align_of, size_of},
+    };
+
+    #[test]
+    fn layout() {
+        assert_eq!(size_of::<CachePadded<u8>>(), 64);
+        assert_eq!(align_of::<CachePadded<u8>>(), 64);
+        assert_eq!(size_of::<CachePadded<[u8; 65]>>(), 128);
+        assert_eq!(size_of::<CachePadded<u32, Align128>>(), 128);
+        assert_eq!(align_of::<CachePadded<u32, Align128>>(), 128);
+    }
+
+    #[test]
+    fn percore_padded() {
+        static STATE: PerCore<[CachePadded<ExceptionLock<RefCell<u32>>>; 2], FakeCoresImpl> =
+            PerCore::new_padded([
+                ExceptionLock::new(RefCell::new(10)),
+                ExceptionLock::new(RefCell::new(20)),
+            ]);
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        FakeCoresImpl::set_core_index(1);
+        assert_eq!(*STATE.get().borrow_mut(token), 20);
+        *STATE.get().borrow_mut(token) += 1;
+        assert_eq!(*STATE.get().borrow_mut(token), 21);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn percore_boxed_padded() {
+        let mut values =
+            PerCore::<Box<[CachePadded<u32, Align128>]>, FakeCoresImpl>::new_padded_with_default(3);
+
+        FakeCoresImpl::set_core_index(2);
+        **values.get_mut() = 42;
+        assert_eq!(
+            values
+                .into_inner()
+                .iter()
+                .map(|value| **value)
+                .collect::<Vec<_>>(),
+            [0, 0, 42]
+        );
+    }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn zerocopy() {
+        use zerocopy::FromZeros;
+
+        let values = PerCore::<[CachePadded<u64, Align128>; 4], FakeCoresImpl>::new_zeroed();
+        assert_eq!(values.into_inner().map(CachePadded::into_inner), [0; 4]);
+    }
+}
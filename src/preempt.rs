@@ -0,0 +1,210 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CoreSync, ExecutionContext, PerCore, Storage};
+use core::{
+    cell::{RefCell, RefMut},
+    marker::PhantomData,
+    ops::Deref,
+};
+
+/// Trait abstracting how to disable and re-enable preemption of the current thread, for systems
+/// where threads may migrate between cores.
+///
+/// # Safety
+///
+/// While preemption is disabled, the current thread must not be migrated to a different core, and
+/// no other thread may run on the current core. Calls to `disable` may be nested, and preemption
+/// must remain disabled until `enable` has been called as many times as `disable`.
+///
+/// `disable` must panic rather than returning if it is called from an exception handler, as
+/// exception handlers may interrupt a thread which has disabled preemption.
+pub unsafe trait Preemption {
+    /// Disables preemption of the current thread.
+    fn disable();
+
+    /// Re-enables preemption of the current thread.
+    ///
+    /// # Safety
+    ///
+    /// This must only be called once for each previous call to `disable` on the same thread, and
+    /// only once nothing that relies on preemption being disabled is still in use.
+    unsafe fn enable();
+}
+
+/// A reference to the value for the current CPU core, which keeps the current thread pinned to the
+/// core by disabling preemption for as long as it exists.
+///
+/// This is returned by [`PerCore::pinned`]. It is not `Send` so it can't be passed to another
+/// thread.
+pub struct Pinned<'a, T, P: Preemption> {
+    value: &'a T,
+    _guard: PreemptGuard<P>,
+}
+
+impl<T, P: Preemption> Deref for Pinned<'_, T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+/// Scope guard which keeps preemption disabled until it is dropped.
+///
+/// This is created before looking up the value for a [`Pinned`], so that preemption is re-enabled
+/// even if the lookup panics.
+struct PreemptGuard<P: Preemption> {
+    _preemption: PhantomData<(P, *const ())>,
+}
+
+impl<P: Preemption> PreemptGuard<P> {
+    /// Disables preemption and returns a guard which will re-enable it when it is dropped.
+    fn new() -> Self {
+        P::disable();
+        Self {
+            _preemption: PhantomData,
+        }
+    }
+}
+
+impl<P: Preemption> Drop for PreemptGuard<P> {
+    fn drop(&mut self) {
+        // SAFETY: Preemption was disabled when the guard was created, and any `Pinned` containing
+        // the guard is being dropped so nothing can use its reference any more.
+        unsafe {
+            P::enable();
+        }
+    }
+}
+
+impl<'a, T, P: Preemption> Pinned<'a, PreemptLock<T>, P> {
+    /// Returns a reference to the contents of the lock.
+    pub fn get(&self) -> &T {
+        &self.value.value
+    }
+}
+
+impl<'a, T, P: Preemption> Pinned<'a, PreemptLock<RefCell<T>>, P> {
+    /// Returns a unique reference to the contents of the `RefCell` in the lock.
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.get().borrow_mut()
+    }
+}
+
+impl<V: Storage, C: ExecutionContext> PerCore<V, C> {
+    /// Disables preemption and returns a reference to the value for the current CPU core, which
+    /// keeps preemption disabled until it is dropped.
+    ///
+    /// This ensures that the current thread can't migrate to another core while it is using the
+    /// value, so the value can't be accessed concurrently by a different core. This may be used
+    /// together with [`PreemptLock`] for state which is never accessed by exception handlers, without
+    /// needing to mask exceptions.
+    ///
+    /// Panics if the current core's index is out of range, or if called from an exception handler.
+    pub fn pinned<P: Preemption>(&self) -> Pinned<'_, V::Item, P> {
+        let guard = PreemptGuard::new();
+        let value = self
+            .try_get()
+            .unwrap_or_else(|e| panic!("Failed to get value for current core: {e}"));
+        Pinned {
+            value,
+            _guard: guard,
+        }
+    }
+}
+
+/// Allows access to the given value only while preemption is disabled, for per-core state which is
+/// never accessed by exception handlers.
+///
+/// This is intended to be stored in a [`PerCore`], and accessed through [`PerCore::pinned`]. As
+/// preemption can't be disabled from exception handlers, they can't access the value.
+#[derive(Default)]
+pub struct PreemptLock<T> {
+    value: T,
+}
+
+impl<T> PreemptLock<T> {
+    /// Creates a new `PreemptLock` containing the given value.
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// Returns a unique reference to the contents of the lock.
+    ///
+    /// This doesn't require preemption to be disabled, as the unique reference to the lock ensures
+    /// that nothing else can access it.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Consumes the `PreemptLock`, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+// SAFETY: The value can only be accessed through a `Pinned` obtained with preemption disabled, which
+// can't happen from an exception handler. So only a single thread on the core can access the value
+// at a time. `T` must be `Send` because the value may still be accessed from different threads at
+// different times.
+unsafe impl<T: Send> CoreSync for PreemptLock<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use std::{cell::Cell, panic::catch_unwind, thread_local};
+
+    thread_local! {
+        /// How many times preemption has been disabled on the current thread without being enabled.
+        static DISABLE_COUNT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct FakePreemption;
+
+    // SAFETY: There is no preemption in the simulated environment of the tests.
+    unsafe impl Preemption for FakePreemption {
+        fn disable() {
+            DISABLE_COUNT.set(DISABLE_COUNT.get() + 1);
+        }
+
+        unsafe fn enable() {
+            DISABLE_COUNT.set(DISABLE_COUNT.get().checked_sub(1).unwrap());
+        }
+    }
+
+    #[test]
+    fn pinned() {
+        static STATE: PerCore<[PreemptLock<RefCell<u32>>; 2], FakeCoresImpl> =
+            PerCore::new([const { PreemptLock::new(RefCell::new(0)) }; 2]);
+
+        FakeCoresImpl::set_core_index(1);
+        {
+            let state = STATE.pinned::<FakePreemption>();
+            assert_eq!(DISABLE_COUNT.get(), 1);
+            *state.borrow_mut() += 1;
+            {
+                let nested = STATE.pinned::<FakePreemption>();
+                assert_eq!(DISABLE_COUNT.get(), 2);
+                assert_eq!(*nested.get().borrow(), 1);
+            }
+            assert_eq!(DISABLE_COUNT.get(), 1);
+        }
+        assert_eq!(DISABLE_COUNT.get(), 0);
+
+        FakeCoresImpl::set_core_index(0);
+        assert_eq!(*STATE.pinned::<FakePreemption>().get().borrow(), 0);
+    }
+
+    #[test]
+    fn pinned_out_of_range() {
+        static STATE: PerCore<[PreemptLock<u32>; 2], FakeCoresImpl> =
+            PerCore::new([const { PreemptLock::new(0) }; 2]);
+
+        FakeCoresImpl::set_core_index(2);
+        assert!(catch_unwind(|| STATE.pinned::<FakePreemption>().get() + 1).is_err());
+        assert_eq!(DISABLE_COUNT.get(), 0);
+    }
+}
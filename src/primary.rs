@@ -0,0 +1,127 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CoreSync, Cores};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+/// A token proving that the current core is the primary core, i.e. that its index is
+/// `C::PRIMARY_CORE_INDEX`.
+///
+/// It is not `Send` or `Sync`, so it can't be passed to a different core.
+pub struct PrimaryCore<C: Cores> {
+    _cores: PhantomData<(C, *const ())>,
+}
+
+impl<C: Cores> PrimaryCore<C> {
+    /// Returns a token if the current core is the primary core, or `None` otherwise.
+    pub fn current() -> Option<Self> {
+        if C::try_core_index() == Some(C::PRIMARY_CORE_INDEX) {
+            // SAFETY: We just checked that the current core is the primary core.
+            Some(unsafe { Self::new_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a token without checking the current core.
+    ///
+    /// # Safety
+    ///
+    /// The current core must be the primary core, i.e. `C::core_index()` must return
+    /// `C::PRIMARY_CORE_INDEX`.
+    pub const unsafe fn new_unchecked() -> Self {
+        Self {
+            _cores: PhantomData,
+        }
+    }
+}
+
+impl<C: Cores> Clone for PrimaryCore<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: Cores> Copy for PrimaryCore<C> {}
+
+impl<C: Cores> Debug for PrimaryCore<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("PrimaryCore").finish()
+    }
+}
+
+/// Allows access to the given value only on the primary core, given a [`PrimaryCore`] token.
+///
+/// This is `Sync` if `T` implements [`CoreSync`], as the value is still shared between the exception
+/// contexts of the primary core. Mutable state can be wrapped in an
+/// [`ExceptionLock`](crate::ExceptionLock).
+#[derive(Default)]
+pub struct PrimaryOnly<T, C: Cores> {
+    _cores: PhantomData<fn() -> C>,
+    value: T,
+}
+
+impl<T, C: Cores> PrimaryOnly<T, C> {
+    /// Creates a new `PrimaryOnly` containing the given value.
+    pub const fn new(value: T) -> Self {
+        Self {
+            _cores: PhantomData,
+            value,
+        }
+    }
+
+    /// Gets a reference to the contents, given a token proving that the current core is the primary
+    /// core.
+    pub fn borrow(&self, _: PrimaryCore<C>) -> &T {
+        &self.value
+    }
+
+    /// Returns a unique reference to the contents.
+    ///
+    /// This doesn't require a token, as the unique reference ensures that nothing else can access
+    /// the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Consumes the `PrimaryOnly`, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+// SAFETY: The value can only be accessed on the primary core, and `T: CoreSync` ensures that it can
+// safely be shared between the exception contexts of that core.
+unsafe impl<T: CoreSync, C: Cores> Sync for PrimaryOnly<T, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExceptionFree, ExceptionLock, tests::FakeCoresImpl};
+    use core::cell::RefCell;
+    use std::thread;
+
+    #[test]
+    fn primary_only() {
+        static STATE: PrimaryOnly<ExceptionLock<RefCell<u32>>, FakeCoresImpl> =
+            PrimaryOnly::new(ExceptionLock::new(RefCell::new(0)));
+
+        thread::spawn(|| {
+            FakeCoresImpl::set_core_index(1);
+            assert!(PrimaryCore::<FakeCoresImpl>::current().is_none());
+        })
+        .join()
+        .unwrap();
+
+        FakeCoresImpl::set_core_index(0);
+        let primary = PrimaryCore::current().unwrap();
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        *STATE.borrow(primary).borrow_mut(token) += 1;
+        assert_eq!(*STATE.borrow(primary).borrow_mut(token), 1);
+    }
+}
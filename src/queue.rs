@@ -0,0 +1,69 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+/// A fixed-capacity first-in first-out queue, which doesn't require allocation.
+pub(crate) struct ArrayQueue<T, const CAP: usize> {
+    slots: [Option<T>; CAP],
+    /// The index in `slots` of the oldest element in the queue.
+    head: usize,
+    /// The number of elements currently in the queue.
+    len: usize,
+}
+
+impl<T, const CAP: usize> ArrayQueue<T, CAP> {
+    /// Creates a new empty queue.
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { None }; CAP],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements currently in the queue.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Adds the given value to the back of the queue, or returns it if the queue is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.len == CAP {
+            return Err(value);
+        }
+        self.slots[(self.head + self.len) % CAP] = Some(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the value at the front of the queue, if any.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.slots[self.head].take();
+        self.head = (self.head + 1) % CAP;
+        self.len -= 1;
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_pop_wraps_around() {
+        let mut queue = ArrayQueue::<u32, 2>::new();
+        assert_eq!(queue.pop(), None);
+        assert_eq!(queue.push(1), Ok(()));
+        assert_eq!(queue.push(2), Ok(()));
+        assert_eq!(queue.push(3), Err(3));
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.push(3), Ok(()));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), None);
+    }
+}
@@ -0,0 +1,262 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+use crate::{Cores, mpidr::read_mpidr};
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+use core::arch::asm;
+use core::{
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+/// A per-core register which can hold a core's index, such as a thread pointer register.
+///
+/// # Safety
+///
+/// Each CPU core must have its own instance of the register. `read` must return the last value
+/// written by `write` on the current core.
+pub unsafe trait ThreadPointer {
+    /// Returns the current value of the register.
+    fn read() -> usize;
+
+    /// Writes the given value to the register.
+    ///
+    /// # Safety
+    ///
+    /// Nothing else may be using the register.
+    unsafe fn write(value: usize);
+}
+
+/// The `TPIDR_EL1` register, for code running at EL1.
+#[cfg(target_arch = "aarch64")]
+pub struct TpidrEl1;
+
+// SAFETY: TPIDR_EL1 is a per-core register, and the value read is the last value written.
+#[cfg(target_arch = "aarch64")]
+unsafe impl ThreadPointer for TpidrEl1 {
+    fn read() -> usize {
+        let value;
+        // SAFETY: Reading TPIDR_EL1 doesn't access memory or have any side effects.
+        unsafe {
+            asm!("mrs {}, tpidr_el1", out(reg) value, options(nomem, nostack, preserves_flags));
+        }
+        value
+    }
+
+    unsafe fn write(value: usize) {
+        // SAFETY: Writing TPIDR_EL1 doesn't access memory, and our caller promises that nothing
+        // else is using it.
+        unsafe {
+            asm!("msr tpidr_el1, {}", in(reg) value, options(nomem, nostack, preserves_flags));
+        }
+    }
+}
+
+/// The `TPIDR_EL2` register, for code running at EL2.
+#[cfg(target_arch = "aarch64")]
+pub struct TpidrEl2;
+
+// SAFETY: TPIDR_EL2 is a per-core register, and the value read is the last value written.
+#[cfg(target_arch = "aarch64")]
+unsafe impl ThreadPointer for TpidrEl2 {
+    fn read() -> usize {
+        let value;
+        // SAFETY: Reading TPIDR_EL2 doesn't access memory or have any side effects.
+        unsafe {
+            asm!("mrs {}, tpidr_el2", out(reg) value, options(nomem, nostack, preserves_flags));
+        }
+        value
+    }
+
+    unsafe fn write(value: usize) {
+        // SAFETY: Writing TPIDR_EL2 doesn't access memory, and our caller promises that nothing
+        // else is using it.
+        unsafe {
+            asm!("msr tpidr_el2, {}", in(reg) value, options(nomem, nostack, preserves_flags));
+        }
+    }
+}
+
+/// The `TPIDRPRW` register, for code running at PL1 on AArch32.
+#[cfg(target_arch = "arm")]
+pub struct Tpidrprw;
+
+// SAFETY: TPIDRPRW is a per-core register, and the value read is the last value written.
+#[cfg(target_arch = "arm")]
+unsafe impl ThreadPointer for Tpidrprw {
+    fn read() -> usize {
+        let value;
+        // SAFETY: Reading TPIDRPRW doesn't access memory or have any side effects.
+        unsafe {
+            asm!(
+                "mrc p15, 0, {}, c13, c0, 4",
+                out(reg) value,
+                options(nomem, nostack, preserves_flags),
+            );
+        }
+        value
+    }
+
+    unsafe fn write(value: usize) {
+        // SAFETY: Writing TPIDRPRW doesn't access memory, and our caller promises that nothing
+        // else is using it.
+        unsafe {
+            asm!(
+                "mcr p15, 0, {}, c13, c0, 4",
+                in(reg) value,
+                options(nomem, nostack, preserves_flags),
+            );
+        }
+    }
+}
+
+/// Assigns dense indices to up to `CORE_COUNT` cores, in the order in which they boot.
+///
+/// The MPIDR of each core is recorded, so that a core which is powered off and later boots again
+/// gets the same index.
+pub struct CoreIndexAllocator<const CORE_COUNT: usize> {
+    /// The number of indices which have been claimed.
+    claimed: AtomicUsize,
+    /// The MPIDR value of the core with each index, with `MPIDR_VALID` set once it has been
+    /// written.
+    mpidrs: [AtomicU64; CORE_COUNT],
+}
+
+/// Flag set in `CoreIndexAllocator::mpidrs` entries once they have been written. This isn't one of
+/// the MPIDR affinity fields, so it doesn't clash with any valid value.
+const MPIDR_VALID: u64 = 1 << 63;
+
+impl<const CORE_COUNT: usize> CoreIndexAllocator<CORE_COUNT> {
+    /// Creates a new allocator with no indices assigned.
+    pub const fn new() -> Self {
+        Self {
+            claimed: AtomicUsize::new(0),
+            mpidrs: [const { AtomicU64::new(0) }; CORE_COUNT],
+        }
+    }
+
+    /// Returns the number of cores which have been assigned indices so far.
+    pub fn count(&self) -> usize {
+        self.claimed.load(Ordering::Acquire).min(CORE_COUNT)
+    }
+
+    /// Returns the MPIDR value of the core with the given index, if it has been assigned.
+    pub fn mpidr(&self, core_index: usize) -> Option<u64> {
+        let mpidr = self.mpidrs.get(core_index)?.load(Ordering::Acquire);
+        (mpidr & MPIDR_VALID != 0).then_some(mpidr & !MPIDR_VALID)
+    }
+
+    /// Returns the index of the core with the given MPIDR value, assigning a new one if it doesn't
+    /// already have one.
+    ///
+    /// Returns `None` if all `CORE_COUNT` indices have already been assigned to other cores.
+    #[cfg(any(test, target_arch = "aarch64", target_arch = "arm"))]
+    fn allocate(&self, mpidr: u64) -> Option<usize> {
+        let entry = (mpidr & !MPIDR_VALID) | MPIDR_VALID;
+        // A core can't race with itself, so if it already has an index then it will be in the
+        // table.
+        if let Some(index) = self
+            .mpidrs
+            .iter()
+            .position(|existing| existing.load(Ordering::Acquire) == entry)
+        {
+            return Some(index);
+        }
+        let index = self.claimed.fetch_add(1, Ordering::AcqRel);
+        self.mpidrs.get(index)?.store(entry, Ordering::Release);
+        Some(index)
+    }
+}
+
+impl<const CORE_COUNT: usize> Default for CoreIndexAllocator<CORE_COUNT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Configuration for [`RegisterCores`], promising that each core initialises its register.
+///
+/// # Safety
+///
+/// Each core must call [`RegisterCores::init`] for this type each time it boots, before anything
+/// calls [`core_index`](crate::Cores::core_index) or accesses any `PerCore` value on it, as the
+/// register has an unknown value after reset. All cores must pass the same allocator to `init`.
+/// Nothing else may use the register `Register`, e.g. to hold the offset for `PercoreLocalOffset`.
+pub unsafe trait RegisterCoresInit {
+    /// The register in which each core's index is cached.
+    type Register: ThreadPointer;
+}
+
+/// An implementation of [`Cores`](crate::Cores) which caches each core's index in a per-core
+/// register, so that looking it up is a single register read.
+///
+/// Each core must call [`init`](Self::init) when it boots, before accessing any `PerCore` values,
+/// as promised by the implementation of [`RegisterCoresInit`] for `I`. This assigns it the next
+/// free index from a [`CoreIndexAllocator`], and writes the index plus one to the register
+/// `I::Register`, or zero if there was no free index.
+///
+/// The register can't be shared with anything else. In particular, if you use the `derive` feature
+/// and keep the offset of each core's percore area in `TPIDR_EL1` (as in the `aarch64_qemu`
+/// example), then you can't also use `TPIDR_EL1` for `RegisterCores`.
+pub struct RegisterCores<I: RegisterCoresInit, const CORE_COUNT: usize>(PhantomData<I>);
+
+impl<I: RegisterCoresInit, const CORE_COUNT: usize> RegisterCores<I, CORE_COUNT> {
+    /// Assigns the current core an index from the given allocator, and stores it in the register.
+    ///
+    /// Returns the index, or `None` if the allocator has already assigned all its indices to other
+    /// cores.
+    ///
+    /// # Safety
+    ///
+    /// No references to any `PerCore` values for the current core may exist, as this may change its
+    /// index if the allocator is different from the one used previously.
+    #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+    pub unsafe fn init(allocator: &CoreIndexAllocator<CORE_COUNT>) -> Option<usize> {
+        let index = allocator.allocate(read_mpidr());
+        // SAFETY: The `RegisterCoresInit` safety contract requires that nothing else is using the
+        // register.
+        unsafe {
+            I::Register::write(index.map_or(0, |index| index + 1));
+        }
+        index
+    }
+}
+
+// SAFETY: The register is per-core, and only written by `init`, which the `RegisterCoresInit`
+// safety contract requires to be called on each core before the register is read. `init` only
+// writes indices assigned by the allocator, which are unique for each core as all cores must use the
+// same allocator, and less than `CORE_COUNT`.
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+unsafe impl<I: RegisterCoresInit, const CORE_COUNT: usize> Cores for RegisterCores<I, CORE_COUNT> {
+    const CORE_COUNT: Option<usize> = Some(CORE_COUNT);
+
+    fn core_index() -> usize {
+        Self::try_core_index().expect("RegisterCores::init not called on current core")
+    }
+
+    fn try_core_index() -> Option<usize> {
+        I::Register::read().checked_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocate() {
+        let allocator = CoreIndexAllocator::<2>::new();
+        assert_eq!(allocator.count(), 0);
+        assert_eq!(allocator.mpidr(0), None);
+
+        assert_eq!(allocator.allocate(0x8000_0100), Some(0));
+        assert_eq!(allocator.allocate(0x8000_0000), Some(1));
+        assert_eq!(allocator.allocate(0x8000_0100), Some(0));
+        assert_eq!(allocator.allocate(0x8000_0200), None);
+        assert_eq!(allocator.count(), 2);
+        assert_eq!(allocator.mpidr(1), Some(0x8000_0000));
+        assert_eq!(allocator.mpidr(2), None);
+    }
+}
@@ -0,0 +1,323 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{CoreSync, ExceptionFree, Storage};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use core::iter::repeat_with;
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering, compiler_fence},
+};
+
+/// A slot in the buffer of an [`SpscRing`], which may or may not contain a value.
+pub struct Slot<T>(UnsafeCell<MaybeUninit<T>>);
+
+impl<T> Slot<T> {
+    /// Creates a new empty slot.
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+}
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An `SpscRing` with a fixed capacity of `CAP` values, stored inline.
+pub type ArraySpscRing<T, const CAP: usize> = SpscRing<T, [Slot<T>; CAP]>;
+
+/// An `SpscRing` with a capacity chosen at runtime, stored in a boxed slice.
+#[cfg(feature = "alloc")]
+pub type BoxedSpscRing<T> = SpscRing<T, Box<[Slot<T>]>>;
+
+/// A ring buffer for passing values from exception handlers to thread context on the same CPU core.
+///
+/// This is intended to be stored in a [`PerCore`](crate::PerCore), with one ring for each core.
+/// Values are pushed by the producer with exceptions masked, usually from an exception handler, and
+/// popped by a single [`SpscConsumer`] in thread context without needing to mask exceptions.
+///
+/// Because the producer and consumer always run on the same core, only compiler fences are needed
+/// to order their accesses, rather than memory barriers.
+pub struct SpscRing<T, S: Storage<Item = Slot<T>>> {
+    /// The position of the next value to pop.
+    ///
+    /// Positions run from 0 to twice the capacity, so that a full ring can be told apart from an
+    /// empty one for any capacity without the counters ever overflowing.
+    head: AtomicUsize,
+    /// The position at which the next value will be pushed.
+    tail: AtomicUsize,
+    /// Whether an `SpscConsumer` currently exists for this ring.
+    consumer_claimed: AtomicBool,
+    slots: S,
+    _value: PhantomData<T>,
+}
+
+impl<T, const CAP: usize> SpscRing<T, [Slot<T>; CAP]> {
+    /// Creates a new empty ring with a fixed capacity of `CAP` values.
+    pub const fn new() -> Self {
+        const { assert!(CAP <= usize::MAX / 2) };
+        Self::with_slots([const { Slot::new() }; CAP])
+    }
+}
+
+impl<T, const CAP: usize> Default for SpscRing<T, [Slot<T>; CAP]> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> SpscRing<T, Box<[Slot<T>]>> {
+    /// Creates a new empty ring which can hold up to `capacity` values.
+    ///
+    /// Panics if `capacity` is more than `usize::MAX / 2`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity <= usize::MAX / 2);
+        Self::with_slots(repeat_with(Slot::new).take(capacity).collect())
+    }
+}
+
+impl<T, S: Storage<Item = Slot<T>>> SpscRing<T, S> {
+    const fn with_slots(slots: S) -> Self {
+        Self {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            consumer_claimed: AtomicBool::new(false),
+            slots,
+            _value: PhantomData,
+        }
+    }
+
+    /// Returns the maximum number of values which the ring can hold.
+    pub fn capacity(&self) -> usize {
+        self.slots.as_slice().len()
+    }
+
+    /// Returns the index of the slot for the given position.
+    fn slot_index(&self, position: usize) -> usize {
+        let capacity = self.capacity();
+        if position >= capacity {
+            position - capacity
+        } else {
+            position
+        }
+    }
+
+    /// Returns the position after the given one, wrapping at twice the capacity.
+    fn next_position(&self, position: usize) -> usize {
+        let next = position + 1;
+        if next == self.capacity() * 2 { 0 } else { next }
+    }
+
+    /// Returns the number of values between the given head and tail positions.
+    fn len_between(&self, head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + self.capacity() * 2 - head
+        }
+    }
+
+    /// Adds the given value to the ring, or returns it if the ring is full.
+    ///
+    /// The token proves that exceptions are masked, so only one producer can be pushing to the ring
+    /// at a time.
+    pub fn push(&self, _token: ExceptionFree, value: T) -> Result<(), T> {
+        let slots = self.slots.as_slice();
+        // Only the producer modifies `tail`, and there is only one producer at a time.
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Relaxed);
+        // Ensure that the consumer has finished reading a slot before we overwrite it.
+        compiler_fence(Ordering::Acquire);
+        if self.len_between(head, tail) == slots.len() {
+            return Err(value);
+        }
+
+        // SAFETY: The slot at `tail` isn't between `head` and `tail`, so the consumer won't access
+        // it until we increment `tail` below, and no other producer can run until we return.
+        unsafe {
+            (*slots[self.slot_index(tail)].0.get()).write(value);
+        }
+
+        // Ensure that the value is written before the consumer sees the new `tail`.
+        compiler_fence(Ordering::Release);
+        self.tail.store(self.next_position(tail), Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns the consumer for the ring, or `None` if there is already one.
+    ///
+    /// The consumer is not `Send`, so it can only be used on the core where it was obtained.
+    pub fn consumer(&self) -> Option<SpscConsumer<'_, T, S>> {
+        if self.consumer_claimed.swap(true, Ordering::Acquire) {
+            None
+        } else {
+            Some(SpscConsumer {
+                ring: self,
+                _not_send: PhantomData,
+            })
+        }
+    }
+}
+
+impl<T, S: Storage<Item = Slot<T>>> Drop for SpscRing<T, S> {
+    fn drop(&mut self) {
+        // Drop any values which haven't been popped.
+        let mut head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        while head != tail {
+            // SAFETY: We have unique access to the ring, and the slots between `head` and `tail` are
+            // initialised.
+            unsafe {
+                (*self.slots.as_slice()[self.slot_index(head)].0.get()).assume_init_drop();
+            }
+            head = self.next_position(head);
+        }
+    }
+}
+
+// SAFETY: Pushing requires exceptions to be masked, so only one producer can access the ring at a
+// time on the core. Only a single `SpscConsumer` can exist at once, and it is not `Send` so it can't
+// be used from a different core. The producer and consumer only access slots which the other isn't
+// using, and synchronise with each other via `head` and `tail` and compiler fences, which is
+// sufficient as they are both on the same core. `T` must be `Send` because values are passed from
+// one exception context to another.
+unsafe impl<T: Send, S: Storage<Item = Slot<T>>> CoreSync for SpscRing<T, S> {}
+
+/// The consumer of an [`SpscRing`], which can pop values from it without masking exceptions.
+pub struct SpscConsumer<'a, T, S: Storage<Item = Slot<T>>> {
+    ring: &'a SpscRing<T, S>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<T, S: Storage<Item = Slot<T>>> SpscConsumer<'_, T, S> {
+    /// Removes and returns the oldest value from the ring, if any.
+    pub fn pop(&mut self) -> Option<T> {
+        let slots = self.ring.slots.as_slice();
+        // Only the consumer modifies `head`, and there is only one consumer.
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        // Ensure that the producer's write to the slot is visible before we read it.
+        compiler_fence(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+
+        // SAFETY: The slot at `head` is between `head` and `tail` so it has been initialised by the
+        // producer, and the producer won't access it again until we increment `head` below.
+        let value = unsafe { (*slots[self.ring.slot_index(head)].0.get()).assume_init_read() };
+
+        // Ensure that we have finished reading the value before the producer can overwrite it.
+        compiler_fence(Ordering::Release);
+        self.ring
+            .head
+            .store(self.ring.next_position(head), Ordering::Relaxed);
+        Some(value)
+    }
+
+    /// Returns the number of values currently in the ring.
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        self.ring.len_between(head, tail)
+    }
+
+    /// Returns whether the ring is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T, S: Storage<Item = Slot<T>>> Drop for SpscConsumer<'_, T, S> {
+    fn drop(&mut self) {
+        self.ring.consumer_claimed.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PerCore, tests::FakeCoresImpl};
+
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn percore_spsc_ring_is_sync() {
+        assert_sync::<PerCore<[ArraySpscRing<u32, 4>; 2], FakeCoresImpl>>();
+        #[cfg(feature = "alloc")]
+        assert_sync::<PerCore<Box<[BoxedSpscRing<u32>]>, FakeCoresImpl>>();
+    }
+
+    #[test]
+    fn push_pop() {
+        let ring = ArraySpscRing::<u32, 2>::new();
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        let mut consumer = ring.consumer().unwrap();
+        assert!(ring.consumer().is_none());
+
+        assert_eq!(consumer.pop(), None);
+        assert_eq!(ring.push(token, 1), Ok(()));
+        assert_eq!(ring.push(token, 2), Ok(()));
+        assert_eq!(ring.push(token, 3), Err(3));
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(consumer.pop(), Some(1));
+        assert_eq!(ring.push(token, 3), Ok(()));
+        assert_eq!(consumer.pop(), Some(2));
+        assert_eq!(consumer.pop(), Some(3));
+        assert_eq!(consumer.pop(), None);
+        assert!(consumer.is_empty());
+
+        drop(consumer);
+        assert!(ring.consumer().is_some());
+    }
+
+    #[test]
+    fn wrap_non_power_of_two() {
+        let ring = ArraySpscRing::<u32, 3>::new();
+        // Start just before the positions wrap.
+        ring.head.store(5, Ordering::Relaxed);
+        ring.tail.store(5, Ordering::Relaxed);
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+        let mut consumer = ring.consumer().unwrap();
+
+        for round in 0..10 {
+            let base = round * 3;
+            assert_eq!(ring.push(token, base), Ok(()));
+            assert_eq!(ring.push(token, base + 1), Ok(()));
+            assert_eq!(ring.push(token, base + 2), Ok(()));
+            assert_eq!(ring.push(token, base + 3), Err(base + 3));
+            assert_eq!(consumer.len(), 3);
+            assert_eq!(consumer.pop(), Some(base));
+            assert_eq!(consumer.pop(), Some(base + 1));
+            assert_eq!(consumer.pop(), Some(base + 2));
+            assert_eq!(consumer.pop(), None);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn boxed_drops_remaining_values() {
+        use std::rc::Rc;
+
+        let value = Rc::new(42);
+        let ring = BoxedSpscRing::with_capacity(3);
+        assert_eq!(ring.capacity(), 3);
+        // SAFETY: There are no exceptions in the simulated environment of the tests.
+        let token = unsafe { ExceptionFree::new() };
+
+        ring.push(token, value.clone()).unwrap();
+        ring.push(token, value.clone()).unwrap();
+        assert_eq!(Rc::strong_count(&value), 3);
+        drop(ring);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+
+/// A contiguous collection of values, such as an array, boxed slice, `Vec` or leaked slice.
+///
+/// # Safety
+///
+/// `as_slice` must always return the same slice for a given instance, as long as the instance isn't
+/// mutated, and must be safe to call concurrently from different threads or exception contexts.
+/// `as_mut_slice` must return the same slice as `as_slice`. If `LEN` is `Some(n)` then the slice
+/// must always have length `n`.
+pub unsafe trait Storage {
+    /// The type of the values in the collection.
+    type Item;
+
+    /// The number of values in the collection, if it is fixed at compile time.
+    const LEN: Option<usize> = None;
+
+    /// Returns the values as a slice.
+    fn as_slice(&self) -> &[Self::Item];
+
+    /// Returns the values as a mutable slice.
+    fn as_mut_slice(&mut self) -> &mut [Self::Item];
+}
+
+// SAFETY: An array always has the same contents, and its length is `N`.
+unsafe impl<T, const N: usize> Storage for [T; N] {
+    type Item = T;
+
+    const LEN: Option<usize> = Some(N);
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+// SAFETY: A boxed slice always points to the same allocation unless it is mutated.
+#[cfg(feature = "alloc")]
+unsafe impl<T> Storage for Box<[T]> {
+    type Item = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+// SAFETY: A `Vec` always points to the same allocation unless it is mutated.
+#[cfg(feature = "alloc")]
+unsafe impl<T> Storage for Vec<T> {
+    type Item = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+// SAFETY: A slice reference always points to the same memory unless it is mutated.
+unsafe impl<T> Storage for &mut [T] {
+    type Item = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PerCore, tests::FakeCoresImpl};
+    use core::{
+        mem::MaybeUninit,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    #[test]
+    fn percore_leaked_slice() {
+        let memory = Box::leak(Box::new([const { MaybeUninit::uninit() }; 3]));
+        let values =
+            PerCore::<&'static mut [AtomicU32], FakeCoresImpl>::new_in(memory, |core_index| {
+                AtomicU32::new(core_index as u32 * 2)
+            });
+
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                FakeCoresImpl::set_core_index(2);
+                values.get().fetch_add(1, Ordering::Relaxed);
+            });
+        });
+        FakeCoresImpl::set_core_index(1);
+        assert_eq!(values.get().load(Ordering::Relaxed), 2);
+        assert_eq!(
+            values.sum::<u32, _>(|value| value.load(Ordering::Relaxed)),
+            7
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn percore_vec() {
+        let mut values = PerCore::<Vec<u32>, FakeCoresImpl>::new(vec![1, 2]);
+        FakeCoresImpl::set_core_index(0);
+        *values.get_mut() += 10;
+        assert_eq!(values.into_inner(), [11, 2]);
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2026 The percore Authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{Cores, PerCore, Storage};
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+use crate::{MpidrCores, Uniform};
+use core::marker::PhantomData;
+
+/// A level of the CPU topology above individual cores, such as [`Cluster`] or [`Package`].
+pub trait TopologyLevel {}
+
+/// A cluster of cores, which usually share an L2 or L3 cache and a power domain.
+pub struct Cluster;
+
+impl TopologyLevel for Cluster {}
+
+/// A package or socket, containing one or more clusters.
+pub struct Package;
+
+impl TopologyLevel for Package {}
+
+/// Extends [`Cores`] with the mapping from cores to groups at the topology level `L`, e.g. from
+/// cores to clusters.
+pub trait Topology<L: TopologyLevel>: Cores {
+    /// Returns the index of the group at level `L` containing the core with the given index.
+    fn group_of(core_index: usize) -> usize;
+
+    /// Returns the index of the group at level `L` containing the current core.
+    fn group_index() -> usize {
+        Self::group_of(Self::core_index())
+    }
+}
+
+/// A value for each cluster of cores.
+pub type PerCluster<V, C> = PerLevel<V, C, Cluster>;
+
+/// A value for each package of cores.
+pub type PerPackage<V, C> = PerLevel<V, C, Package>;
+
+/// A type which allows values to be stored per group of cores at some level of the CPU topology,
+/// such as [`PerCluster`].
+///
+/// Unlike [`PerCore`], each value is shared by all the cores in its group, so only values which are
+/// `Sync` may be accessed. An [`ExceptionLock`](crate::ExceptionLock) on its own is not enough, as
+/// it only prevents concurrent access from different exception contexts on the same core; it can be
+/// combined with a spinlock to protect against other cores in the group too.
+#[repr(transparent)]
+pub struct PerLevel<V: ?Sized, C: Topology<L>, L: TopologyLevel> {
+    _cores: PhantomData<fn() -> (C, L)>,
+    values: V,
+}
+
+impl<V, C: Topology<L>, L: TopologyLevel> PerLevel<V, C, L> {
+    /// Creates a new set of per-group values.
+    pub const fn new(values: V) -> Self {
+        Self {
+            values,
+            _cores: PhantomData,
+        }
+    }
+
+    /// Consumes the `PerLevel`, returning the wrapped value.
+    pub fn into_inner(self) -> V {
+        self.values
+    }
+}
+
+impl<V: Storage, C: Topology<L>, L: TopologyLevel> PerLevel<V, C, L>
+where
+    V::Item: Sync,
+{
+    /// Gets a shared reference to the value for the group containing the current CPU core.
+    ///
+    /// Panics if the group index is out of range.
+    pub fn get(&self) -> &V::Item {
+        &self.values.as_slice()[C::group_index()]
+    }
+
+    /// Gets a shared reference to the value for the group with the given index, or `None` if there
+    /// is no value for that index.
+    pub fn get_for(&self, group_index: usize) -> Option<&V::Item> {
+        self.values.as_slice().get(group_index)
+    }
+
+    /// Returns an iterator over the values for all groups, in order of group index.
+    pub fn iter(&self) -> core::slice::Iter<'_, V::Item> {
+        self.values.as_slice().iter()
+    }
+}
+
+impl<V: Storage, C: Cores> PerCore<V, C>
+where
+    V::Item: Sync,
+{
+    /// Returns an iterator over the index and value of each core in the group with the given index
+    /// at topology level `L`, e.g. each core in a cluster.
+    pub fn iter_group<L: TopologyLevel>(
+        &self,
+        group_index: usize,
+    ) -> impl Iterator<Item = (usize, &V::Item)>
+    where
+        C: Topology<L>,
+    {
+        self.values
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter(move |(core_index, _)| C::group_of(*core_index) == group_index)
+    }
+
+    /// Folds the values for all cores in the group with the given index at topology level `L` into
+    /// a single value, e.g. to sum the per-core values of a cluster.
+    pub fn fold_group<L: TopologyLevel, B>(
+        &self,
+        group_index: usize,
+        init: B,
+        mut f: impl FnMut(B, &V::Item) -> B,
+    ) -> B
+    where
+        C: Topology<L>,
+    {
+        self.iter_group::<L>(group_index)
+            .fold(init, |acc, (_, value)| f(acc, value))
+    }
+}
+
+/// Cores are grouped into `CLUSTERS` clusters in order of core index.
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+impl<const CLUSTERS: usize, const CORES: usize, const THREADS: usize, const MT: bool>
+    Topology<Cluster> for MpidrCores<Uniform<CLUSTERS, CORES, THREADS, MT>>
+{
+    fn group_of(core_index: usize) -> usize {
+        core_index / (CORES * THREADS)
+    }
+}
+
+/// A uniform layout has no package level in its MPIDR values, so all cores are in a single package.
+#[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+impl<const CLUSTERS: usize, const CORES: usize, const THREADS: usize, const MT: bool>
+    Topology<Package> for MpidrCores<Uniform<CLUSTERS, CORES, THREADS, MT>>
+{
+    fn group_of(_core_index: usize) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeCoresImpl;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fake cores in clusters of two.
+    struct ClusteredCores;
+
+    // SAFETY: `FakeCoresImpl` returns a unique index for each simulated core.
+    unsafe impl Cores for ClusteredCores {
+        fn core_index() -> usize {
+            FakeCoresImpl::core_index()
+        }
+    }
+
+    impl Topology<Cluster> for ClusteredCores {
+        fn group_of(core_index: usize) -> usize {
+            core_index / 2
+        }
+    }
+
+    impl Topology<Package> for ClusteredCores {
+        fn group_of(_core_index: usize) -> usize {
+            0
+        }
+    }
+
+    #[test]
+    fn per_cluster() {
+        static STATE: PerCluster<[AtomicU32; 2], ClusteredCores> =
+            PerCluster::new([const { AtomicU32::new(0) }; 2]);
+
+        for core_index in [1, 2, 3] {
+            FakeCoresImpl::set_core_index(core_index);
+            STATE.get().fetch_add(1, Ordering::Relaxed);
+        }
+
+        assert_eq!(<ClusteredCores as Topology<Cluster>>::group_index(), 1);
+        assert_eq!(STATE.get_for(0).unwrap().load(Ordering::Relaxed), 1);
+        assert_eq!(
+            STATE
+                .iter()
+                .map(|value| value.load(Ordering::Relaxed))
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+    }
+
+    #[test]
+    fn per_package() {
+        static STATE: PerPackage<[AtomicU32; 1], ClusteredCores> =
+            PerPackage::new([const { AtomicU32::new(0) }; 1]);
+
+        for core_index in [0, 3] {
+            FakeCoresImpl::set_core_index(core_index);
+            STATE.get().fetch_add(1, Ordering::Relaxed);
+        }
+
+        assert_eq!(<ClusteredCores as Topology<Package>>::group_index(), 0);
+        assert_eq!(STATE.get_for(0).unwrap().load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn aggregate_cluster() {
+        let values = PerCore::<_, ClusteredCores>::new([
+            AtomicU32::new(1),
+            AtomicU32::new(2),
+            AtomicU32::new(3),
+            AtomicU32::new(4),
+            AtomicU32::new(5),
+        ]);
+
+        assert_eq!(
+            values
+                .iter_group::<Cluster>(1)
+                .map(|(core_index, _)| core_index)
+                .collect::<Vec<_>>(),
+            [2, 3]
+        );
+        let sums = [0, 1, 2].map(|cluster| {
+            values.fold_group::<Cluster, _>(cluster, 0, |sum, value| {
+                sum + value.load(Ordering::Relaxed)
+            })
+        });
+        assert_eq!(sums, [3, 7, 5]);
+    }
+}